@@ -0,0 +1,260 @@
+use anyhow::{ Result, Context };
+use serde::{ Deserialize, Serialize };
+use std::fmt;
+use std::path::PathBuf;
+
+use chrono::{ SecondsFormat, Utc };
+
+use crate::client::Outcome;
+use crate::common::{ AocDate, Part, RunReport };
+
+/// Accepted answers of a single day, stored as `<root>/<year>/day<N>.toml`
+/// along with the history of answers submitted to the site.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub submissions: Vec<Submission>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Submission {
+    pub part: Part,
+    pub answer: String,
+    pub outcome: Outcome,
+    pub at: String,
+}
+
+impl Answers {
+    pub fn get(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part1.as_deref(),
+            Part::Two => self.part2.as_deref(),
+        }
+    }
+
+    pub fn set(&mut self, part: Part, answer: &str) {
+        let slot = match part {
+            Part::One => &mut self.part1,
+            Part::Two => &mut self.part2,
+        };
+
+        *slot = Some(answer.to_string());
+    }
+
+    /// Explains why submitting `answer` would be pointless given what is already known:
+    /// the part is solved, the same answer was rejected, or it lies outside known bounds.
+    pub fn rejection(&self, part: Part, answer: &str) -> Option<String> {
+        if let Some(accepted) = self.get(part) {
+            return Some(format!("part {} is already solved with {}", part, accepted));
+        }
+
+        let value = answer.trim().parse::<i128>().ok();
+
+        for s in self.submissions.iter().filter(|s| s.part == part) {
+            if s.answer == answer {
+                return Some(format!("{} was already submitted on {} and was {}", answer, s.at, s.outcome));
+            }
+
+            let bound = s.answer.trim().parse::<i128>().ok();
+
+            match (&s.outcome, value, bound) {
+                (Outcome::TooHigh, Some(v), Some(b)) if v >= b =>
+                    return Some(format!("{} is not lower than {}, which was too high", answer, s.answer)),
+                (Outcome::TooLow, Some(v), Some(b)) if v <= b =>
+                    return Some(format!("{} is not higher than {}, which was too low", answer, s.answer)),
+                _ => (),
+            }
+        }
+
+        None
+    }
+}
+
+pub struct AnswerStore {
+    root: PathBuf,
+}
+
+impl AnswerStore {
+    pub fn new(root: PathBuf) -> Self {
+        AnswerStore { root }
+    }
+
+    fn file_path(&self, date: &AocDate) -> PathBuf {
+        let mut path_buf = self.root.clone();
+        path_buf.push(date.year.to_string());
+        path_buf.push(format!("day{}.toml", date.day));
+
+        path_buf
+    }
+
+    /// Loads answers recorded for a day, which are empty if nothing was recorded yet.
+    pub fn load(&self, date: &AocDate) -> Result<Answers> {
+        let path = self.file_path(date);
+
+        if !path.exists() {
+            return Ok(Answers::default());
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Unable to read answers from {}", path.to_string_lossy()))?;
+
+        toml::from_str(&content)
+            .with_context(|| format!("Malformed answers file {}", path.to_string_lossy()))
+    }
+
+    pub fn save(&self, date: &AocDate, answers: &Answers) -> Result<()> {
+        let path = self.file_path(date);
+        std::fs::create_dir_all(path.parent().unwrap())?;
+
+        std::fs::write(&path, toml::to_string(answers)?)
+            .with_context(|| format!("Unable to store answers in {}", path.to_string_lossy()))
+    }
+
+    pub fn record(&self, date: &AocDate, part: Part, answer: &str) -> Result<()> {
+        let mut answers = self.load(date)?;
+        answers.set(part, answer);
+
+        self.save(date, &answers)
+    }
+
+    /// Remembers a definitive site verdict; a correct answer also becomes the accepted one.
+    pub fn record_submission(&self, date: &AocDate, part: Part, answer: &str, outcome: &Outcome) -> Result<()> {
+        let mut answers = self.load(date)?;
+
+        if *outcome == Outcome::Correct {
+            answers.set(part, answer);
+        }
+
+        answers.submissions.push(Submission {
+            part,
+            answer: answer.to_string(),
+            outcome: outcome.clone(),
+            at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        });
+
+        self.save(date, &answers)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Mismatch { actual: String, expected: String },
+    Unrecorded,
+    Failed(String),
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "ok"),
+            Verdict::Mismatch { actual, expected } => write!(f, "MISMATCH: got {}, expected {}", actual, expected),
+            Verdict::Unrecorded => write!(f, "no recorded answer"),
+            Verdict::Failed(error) => write!(f, "FAILED: {}", error),
+        }
+    }
+}
+
+/// Compares the outcome of a run against the recorded answers, part by part.
+pub fn check(report: &RunReport, answers: &Answers) -> Vec<(Part, Verdict)> {
+    Part::ALL.iter()
+        .map(|&part| {
+            let verdict = match (&report.error, report.part(part), answers.get(part)) {
+                (Some(error), _, _) => Verdict::Failed(error.clone()),
+                (None, None, _) => Verdict::Failed("not run".to_string()),
+                (None, Some(p), expected) => match (&p.answer, expected) {
+                    (Err(error), _) => Verdict::Failed(error.clone()),
+                    (Ok(_), None) => Verdict::Unrecorded,
+                    (Ok(actual), Some(expected)) if actual == expected => Verdict::Pass,
+                    (Ok(actual), Some(expected)) => Verdict::Mismatch { actual: actual.clone(), expected: expected.to_string() },
+                },
+            };
+
+            (part, verdict)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::PartReport;
+    use std::time::Duration;
+
+    #[test]
+    pub fn test_answer_store_round_trip() {
+        let root = std::env::temp_dir().join(format!("aoc-rs-answers-{}", std::process::id()));
+        let store = AnswerStore::new(root.clone());
+        let date = AocDate { year: 2021, day: 7 };
+
+        assert_eq!(store.load(&date).unwrap(), Answers::default());
+
+        store.record(&date, Part::Two, "168").unwrap();
+        store.record(&date, Part::One, "37").unwrap();
+
+        assert_eq!(std::fs::read_to_string(root.join("2021").join("day7.toml")).unwrap(), "part1 = \"37\"\npart2 = \"168\"\n");
+        assert_eq!(store.load(&date).unwrap().get(Part::Two), Some("168"));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    pub fn test_check_against_answers() {
+        let part = |part, answer: std::result::Result<&str, &str>| PartReport {
+            part,
+            answer: answer.map(|a| a.to_string()).map_err(|e| e.to_string()),
+            elapsed: Duration::ZERO,
+        };
+        let date = AocDate { year: 2021, day: 1 };
+        let answers = Answers { part1: Some("7".to_string()), part2: Some("5".to_string()), ..Default::default() };
+
+        let report = RunReport { parts: vec![part(Part::One, Ok("7")), part(Part::Two, Ok("6"))], ..RunReport::new(date) };
+        assert_eq!(check(&report, &answers), vec![
+            (Part::One, Verdict::Pass),
+            (Part::Two, Verdict::Mismatch { actual: "6".to_string(), expected: "5".to_string() }),
+        ]);
+
+        let report = RunReport { parts: vec![part(Part::One, Err("boom"))], ..RunReport::new(date) };
+        assert_eq!(check(&report, &Answers::default()), vec![
+            (Part::One, Verdict::Failed("boom".to_string())),
+            (Part::Two, Verdict::Failed("not run".to_string())),
+        ]);
+
+        let report = RunReport { parts: vec![part(Part::One, Ok("7")), part(Part::Two, Ok("5"))], ..RunReport::new(date) };
+        assert_eq!(check(&report, &Answers { part2: Some("5".to_string()), ..Default::default() }), vec![
+            (Part::One, Verdict::Unrecorded),
+            (Part::Two, Verdict::Pass),
+        ]);
+    }
+
+    #[test]
+    pub fn test_submission_guard() {
+        let root = std::env::temp_dir().join(format!("aoc-rs-submissions-{}", std::process::id()));
+        let store = AnswerStore::new(root.clone());
+        let date = AocDate { year: 2021, day: 7 };
+
+        store.record_submission(&date, Part::One, "500", &Outcome::TooHigh).unwrap();
+        store.record_submission(&date, Part::One, "20", &Outcome::TooLow).unwrap();
+        store.record_submission(&date, Part::One, "42", &Outcome::Wrong).unwrap();
+
+        let answers = store.load(&date).unwrap();
+        assert_eq!(answers.submissions.len(), 3);
+        assert!(answers.rejection(Part::One, "42").is_some());
+        assert!(answers.rejection(Part::One, "500").is_some());
+        assert!(answers.rejection(Part::One, "501").is_some());
+        assert!(answers.rejection(Part::One, "19").is_some());
+        assert_eq!(answers.rejection(Part::One, "37"), None);
+        assert_eq!(answers.rejection(Part::Two, "42"), None);
+
+        store.record_submission(&date, Part::One, "37", &Outcome::Correct).unwrap();
+
+        let answers = store.load(&date).unwrap();
+        assert_eq!(answers.get(Part::One), Some("37"));
+        assert!(answers.rejection(Part::One, "37").is_some());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,104 @@
+use anyhow::{ Result, anyhow };
+use std::time::{ Duration, Instant };
+
+use crate::common::Part;
+
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+
+        let n = sorted.len();
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+
+        let secs: Vec<f64> = sorted.iter().map(|d| d.as_secs_f64()).collect();
+        let mean = secs.iter().sum::<f64>() / n as f64;
+        let variance = secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n as f64;
+
+        Some(Stats {
+            runs: n,
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+/// Runs a part `warmup` times without measuring, then `iterations` times measuring each run.
+/// `solver` works on an input parsed beforehand, so only the part itself is measured.
+/// The first warmup run always happens and provides the reference answer, which must stay
+/// the same across the measured runs.
+pub fn run(solver: &dyn Fn(Part) -> Result<String>, part: Part, warmup: usize, iterations: usize) -> Result<(String, Stats)> {
+    let answer = solver(part)?;
+
+    for _ in 1..warmup {
+        solver(part)?;
+    }
+
+    let mut samples = Vec::with_capacity(iterations);
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let result = solver(part)?;
+        samples.push(start.elapsed());
+
+        if result != answer {
+            return Err(anyhow!("Answer changed between runs: {} vs {}", answer, result));
+        }
+    }
+
+    let stats = Stats::from_samples(&samples).ok_or_else(|| anyhow!("At least one iteration is required"))?;
+
+    Ok((answer, stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_stats() {
+        let samples: Vec<Duration> = [4, 2, 9, 5, 5, 4, 7, 4].iter().map(|ms| Duration::from_millis(*ms)).collect();
+        let stats = Stats::from_samples(&samples).unwrap();
+
+        assert_eq!(stats.runs, 8);
+        assert_eq!(stats.min, Duration::from_millis(2));
+        assert_eq!(stats.median, Duration::from_millis(4) + Duration::from_micros(500));
+        assert_eq!(stats.mean.as_micros(), 5000);
+        assert_eq!(stats.stddev.as_micros(), 2000);
+
+        assert_eq!(Stats::from_samples(&[]), None);
+    }
+
+    #[test]
+    pub fn test_run() {
+        let calls = std::cell::Cell::new(0);
+        let count = |_: Part| {
+            calls.set(calls.get() + 1);
+            Ok(calls.get().to_string())
+        };
+
+        let (answer, stats) = run(&|part| count(part).map(|_| "42".to_string()), Part::Two, 3, 5).unwrap();
+        assert_eq!((answer.as_str(), stats.runs, calls.get()), ("42", 5, 8));
+        assert!(run(&count, Part::One, 1, 2).is_err());
+        assert!(run(&|_| Ok(String::new()), Part::One, 1, 0).is_err());
+    }
+}
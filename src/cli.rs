@@ -0,0 +1,310 @@
+use anyhow::{ Result, anyhow };
+use clap::{ Args, Parser, Subcommand };
+use log::LevelFilter;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::common::{ AocDate, Part, Parts };
+use crate::report::Format;
+
+#[derive(Parser)]
+#[command(name = "aoc-rs", version, about = "Advent of Code solutions runner")]
+pub struct Cli {
+    /// Logging verbosity: off, error, warn, info, debug or trace
+    #[arg(long, global = true, default_value = "info")]
+    pub log_level: LevelFilter,
+
+    /// AoC session token (default: AOC_TOKEN, then .aoc-session files, then the account's or top-level session of the config file)
+    #[arg(long, global = true, value_name = "TOKEN")]
+    pub token: Option<String>,
+
+    /// Config file (default: ~/.config/aoc-rs/config.toml)
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Root of the input cache, overriding AOC_INPUT_DIR and the config file (default: ~/.cache/aoc-rs)
+    #[arg(long, global = true, value_name = "DIR")]
+    pub input_dir: Option<PathBuf>,
+
+    /// Root of the recorded answers, overriding AOC_DATA_DIR and the config file (default: ~/.local/share/aoc-rs)
+    #[arg(long, global = true, value_name = "DIR")]
+    pub data_dir: Option<PathBuf>,
+
+    /// Account whose inputs and answers are used, overriding AOC_ACCOUNT and the config file
+    #[arg(long, global = true)]
+    pub account: Option<String>,
+
+    /// Print run results as table, json or csv (batch runs default to table, solve only logs them)
+    #[arg(long, global = true)]
+    pub format: Option<Format>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+/// Solves every registered day of a year, or a range of its days, when no command is given.
+#[derive(Args)]
+pub struct RunArgs {
+    /// Year to solve (today's puzzle only if omitted)
+    pub year: Option<u32>,
+
+    /// Days to solve: a single day or a range like 1..=11, 3..7, 5..
+    #[arg(requires = "year")]
+    pub days: Option<DayRange>,
+}
+
+#[derive(Args)]
+pub struct SelectArgs {
+    /// Year to select (all registered years if omitted)
+    pub year: Option<u32>,
+
+    /// Days to select: a single day or a range like 1..=11, 3..7, 5..
+    #[arg(requires = "year")]
+    pub days: Option<DayRange>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DayRange {
+    pub first: u32,
+    pub last: u32,
+}
+
+impl DayRange {
+    pub fn contains(&self, day: u32) -> bool {
+        (self.first..=self.last).contains(&day)
+    }
+}
+
+impl FromStr for DayRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parse_day = |v: &str| v.trim().parse::<u32>()
+            .map_err(|e| anyhow!("Invalid day '{}': {}", v, e));
+
+        let range = match s.split_once("..") {
+            Some((first, last)) => {
+                let first = if first.is_empty() { 1 } else { parse_day(first)? };
+                let last = match last.strip_prefix('=') {
+                    Some(last) => parse_day(last)?,
+                    None if last.is_empty() => 25,
+                    None => parse_day(last)?.saturating_sub(1),
+                };
+                DayRange { first, last }
+            },
+            None => {
+                let day = parse_day(s)?;
+                DayRange { first: day, last: day }
+            },
+        };
+
+        if range.first == 0 || range.first > range.last {
+            Err(anyhow!("Empty or invalid day range '{}'", s))
+        } else {
+            Ok(range)
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Solve a puzzle (today's one if no date is given)
+    Solve {
+        #[command(flatten)]
+        args: SolveArgs,
+
+        /// Wait for a locked puzzle to unlock, then fetch its input and solve it
+        #[arg(long)]
+        wait: bool,
+    },
+    /// Download puzzle input into the local cache
+    Fetch {
+        #[command(flatten)]
+        date: DateArgs,
+
+        /// Wait for a locked puzzle to unlock, then fetch its input
+        #[arg(long)]
+        wait: bool,
+    },
+    /// Generate and register the module of a new day from the template
+    New {
+        #[command(flatten)]
+        date: DateArgs,
+
+        /// Also download the input and take the example from the puzzle description
+        #[arg(long)]
+        fetch: bool,
+    },
+    /// Show a puzzle description, downloading it into the cache the first time
+    Puzzle(PuzzleArgs),
+    /// List registered solutions
+    List {
+        /// Only list solutions of this year
+        #[arg(short, long)]
+        year: Option<u32>,
+    },
+    /// Run a solution against an input and check the answers
+    Test(TestArgs),
+    /// Measure how long each part of a solution takes
+    Bench(BenchArgs),
+    /// Re-run solutions and compare them against recorded answers
+    Verify(SelectArgs),
+    /// Run solutions against the example inputs in examples/<year>/day<N>/
+    Examples(SelectArgs),
+    /// Record accepted answers of a day (the computed ones if no answer is given)
+    Record(RecordArgs),
+    /// Submit an answer to adventofcode.com (the computed one if no answer is given)
+    Submit(RecordArgs),
+}
+
+#[derive(Args)]
+pub struct DateArgs {
+    /// Puzzle year (current year by default)
+    #[arg(short, long)]
+    pub year: Option<u32>,
+
+    /// Puzzle day (today by default)
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..=25))]
+    pub day: Option<u32>,
+}
+
+impl DateArgs {
+    pub fn date(&self) -> Result<AocDate> {
+        let date = match (self.year, self.day) {
+            (Some(year), Some(day)) => AocDate { year, day },
+            (Some(year), None) => AocDate { year, day: AocDate::today().day },
+            (None, Some(day)) => AocDate::day(day as i32),
+            (None, None) => AocDate::today(),
+        };
+
+        date.validate()?;
+        Ok(date)
+    }
+}
+
+#[derive(Args)]
+pub struct PuzzleArgs {
+    #[command(flatten)]
+    pub date: DateArgs,
+
+    /// Render as markdown instead of plain text
+    #[arg(long)]
+    pub markdown: bool,
+
+    /// Download the description again, e.g. to get part 2 once part 1 is solved
+    #[arg(long)]
+    pub refresh: bool,
+
+    /// Write a code block of the description to examples/<year>/day<N>/<name>.txt instead of showing it
+    #[arg(long)]
+    pub extract: bool,
+
+    /// Code block to extract, counting from 1
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub block: u32,
+
+    /// Name of the extracted example
+    #[arg(long, default_value = "example")]
+    pub name: String,
+
+    /// Overwrite an existing non-empty example file
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Args)]
+pub struct SolveArgs {
+    #[command(flatten)]
+    pub date: DateArgs,
+
+    /// Parts of the puzzle to solve: 1, 2 or both (default: both)
+    #[arg(short, long)]
+    pub part: Option<Parts>,
+
+    /// Read input from a file, or from stdin with '-', instead of the input cache
+    #[arg(short, long, value_name = "FILE|-")]
+    pub input: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct TestArgs {
+    #[command(flatten)]
+    pub solve: SolveArgs,
+
+    /// Expected answer for part 1
+    #[arg(long, value_name = "ANSWER")]
+    pub expect1: Option<String>,
+
+    /// Expected answer for part 2
+    #[arg(long, value_name = "ANSWER")]
+    pub expect2: Option<String>,
+}
+
+#[derive(Args)]
+pub struct BenchArgs {
+    #[command(flatten)]
+    pub solve: SolveArgs,
+
+    /// Number of measured runs per part
+    #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    pub iterations: u32,
+
+    /// Number of unmeasured runs per part before measuring
+    #[arg(short, long, default_value_t = 3)]
+    pub warmup: u32,
+}
+
+#[derive(Args)]
+pub struct RecordArgs {
+    #[command(flatten)]
+    pub solve: SolveArgs,
+
+    /// Answer for the given part
+    #[arg(requires = "part")]
+    pub answer: Option<String>,
+}
+
+impl SolveArgs {
+    pub fn parts(&self) -> Parts {
+        self.part.unwrap_or(Parts::Both)
+    }
+}
+
+impl RecordArgs {
+    /// The part given on the command line; answers are recorded and submitted one part at a time.
+    pub fn part(&self) -> Result<Option<Part>> {
+        match self.solve.part {
+            Some(Parts::Both) if self.answer.is_some() => Err(anyhow!("An answer can only be given for a single part")),
+            parts => Ok(parts.and_then(Parts::single)),
+        }
+    }
+}
+
+impl TestArgs {
+    pub fn expected(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.expect1.as_deref(),
+            Part::Two => self.expect2.as_deref(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_day_range_parsing() {
+        assert_eq!("5".parse::<DayRange>().unwrap(), DayRange { first: 5, last: 5 });
+        assert_eq!("1..=11".parse::<DayRange>().unwrap(), DayRange { first: 1, last: 11 });
+        assert_eq!("3..7".parse::<DayRange>().unwrap(), DayRange { first: 3, last: 6 });
+        assert_eq!("20..".parse::<DayRange>().unwrap(), DayRange { first: 20, last: 25 });
+        assert_eq!("..=4".parse::<DayRange>().unwrap(), DayRange { first: 1, last: 4 });
+        assert!("7..3".parse::<DayRange>().is_err());
+        assert!("0".parse::<DayRange>().is_err());
+        assert!("x..=3".parse::<DayRange>().is_err());
+    }
+}
@@ -0,0 +1,354 @@
+extern crate reqwest;
+
+use anyhow::{ Result, anyhow };
+use lazy_static::lazy_static;
+use log::warn;
+use regex::Regex;
+use reqwest::blocking::{ Client, RequestBuilder };
+use reqwest::header::COOKIE;
+use reqwest::StatusCode;
+use serde::{ Deserialize, Serialize };
+use std::fmt;
+use std::time::Duration;
+
+use crate::common::{ AocDate, Part };
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+
+/// Identifies the tool to the site operators, as they ask automated tools to do.
+pub const DEFAULT_USER_AGENT: &str = "github.com/dshipilov/aoc-rs by denis@pagebox.io";
+
+/// Authenticated access to the Advent of Code site.
+pub struct AocClient {
+    base_url: String,
+    token: String,
+    http: Client,
+    retries: u32,
+    retry_delay: Duration,
+}
+
+/// What the site replied to a submitted answer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    RateLimited(Duration),
+    AlreadySolved,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "correct"),
+            Outcome::TooHigh => write!(f, "too high"),
+            Outcome::TooLow => write!(f, "too low"),
+            Outcome::Wrong => write!(f, "wrong"),
+            Outcome::RateLimited(wait) => write!(f, "rate limited, {}s left to wait", wait.as_secs()),
+            Outcome::AlreadySolved => write!(f, "already solved"),
+        }
+    }
+}
+
+impl Outcome {
+    /// Interprets the HTML page returned after posting an answer.
+    pub fn from_html(html: &str) -> Result<Self> {
+        lazy_static! {
+            static ref WAIT: Regex = Regex::new(r"You have ((?:\d+[hms]\s*)+) left to wait").unwrap();
+            static ref WAIT_UNIT: Regex = Regex::new(r"(\d+)([hms])").unwrap();
+        }
+
+        if html.contains("That's the right answer") {
+            Ok(Outcome::Correct)
+        } else if html.contains("your answer is too high") {
+            Ok(Outcome::TooHigh)
+        } else if html.contains("your answer is too low") {
+            Ok(Outcome::TooLow)
+        } else if html.contains("That's not the right answer") {
+            Ok(Outcome::Wrong)
+        } else if html.contains("You gave an answer too recently") {
+            let seconds = WAIT.captures(html)
+                .map(|caps| WAIT_UNIT.captures_iter(&caps[1])
+                    .map(|unit| {
+                        let n: u64 = unit[1].parse().unwrap_or(0);
+                        match &unit[2] {
+                            "h" => n * 3600,
+                            "m" => n * 60,
+                            _ => n,
+                        }
+                    })
+                    .sum())
+                .unwrap_or(60);
+
+            Ok(Outcome::RateLimited(Duration::from_secs(seconds)))
+        } else if html.contains("You don't seem to be solving the right level") {
+            Ok(Outcome::AlreadySolved)
+        } else {
+            Err(anyhow!("Unrecognized answer response"))
+        }
+    }
+}
+
+/// Why a request to the site failed.
+#[derive(Debug, PartialEq, Eq)]
+pub enum FetchError {
+    /// The site rejected the session cookie (HTTP 400)
+    SessionExpired,
+    /// The puzzle does not exist or is not unlocked yet (HTTP 404)
+    NotUnlocked,
+    /// The site failed to handle the request (HTTP 5xx)
+    Server(StatusCode),
+    /// Any other unexpected HTTP status
+    Unexpected(StatusCode),
+    /// The request did not get a response, e.g. a connection failure or timeout
+    Transport(String),
+}
+
+impl FetchError {
+    fn from_status(status: StatusCode) -> Self {
+        match status {
+            StatusCode::BAD_REQUEST => FetchError::SessionExpired,
+            StatusCode::NOT_FOUND => FetchError::NotUnlocked,
+            s if s.is_server_error() => FetchError::Server(s),
+            s => FetchError::Unexpected(s),
+        }
+    }
+
+    /// Failures worth retrying: the same request may succeed a moment later.
+    fn is_transient(&self) -> bool {
+        matches!(self, FetchError::Server(_) | FetchError::Transport(_))
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::SessionExpired => write!(f, "AoC session token was rejected, it is probably expired; log in again and update it"),
+            FetchError::NotUnlocked => write!(f, "Puzzle is not unlocked yet"),
+            FetchError::Server(code) => write!(f, "AoC server error ({})", code),
+            FetchError::Unexpected(code) => write!(f, "Got unexpected response with code {}", code),
+            FetchError::Transport(error) => write!(f, "Request failed: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+pub struct ClientOptions {
+    pub user_agent: String,
+    /// Limit for a whole request, including reading the response
+    pub timeout: Duration,
+    pub connect_timeout: Duration,
+    /// How many times a failed download is retried
+    pub retries: u32,
+    /// Wait before the first retry, doubled for each next one
+    pub retry_delay: Duration,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        ClientOptions {
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            timeout: Duration::from_secs(30),
+            connect_timeout: Duration::from_secs(10),
+            retries: 3,
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl AocClient {
+    pub fn new(token: &str, options: ClientOptions) -> Result<Self> {
+        AocClient::with_base_url(BASE_URL, token, options)
+    }
+
+    pub fn with_base_url(base_url: &str, token: &str, options: ClientOptions) -> Result<Self> {
+        let http = Client::builder()
+            .user_agent(options.user_agent.as_str())
+            .timeout(options.timeout)
+            .connect_timeout(options.connect_timeout)
+            .build()?;
+
+        Ok(AocClient {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            http,
+            retries: options.retries,
+            retry_delay: options.retry_delay,
+        })
+    }
+
+    fn url(&self, date: &AocDate, resource: &str) -> String {
+        format!("{}/{}/day/{}{}", self.base_url, date.year, date.day, resource)
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.token)
+    }
+
+    /// Sends a request and returns the body of a successful response.
+    /// Transient failures are retried only if `retry` is set, so non-idempotent
+    /// requests are never sent twice.
+    fn send(&self, request: impl Fn() -> RequestBuilder, retry: bool) -> std::result::Result<String, FetchError> {
+        let mut attempt = 0;
+
+        loop {
+            let error = match request().header(COOKIE, self.cookie()).send() {
+                Ok(response) if response.status().is_success() =>
+                    return response.text().map_err(|e| FetchError::Transport(e.to_string())),
+                Ok(response) => FetchError::from_status(response.status()),
+                Err(e) => FetchError::Transport(e.to_string()),
+            };
+
+            if !retry || !error.is_transient() || attempt >= self.retries {
+                return Err(error);
+            }
+
+            let delay = self.retry_delay * 2u32.pow(attempt);
+            attempt += 1;
+
+            warn!("{}, retrying in {:?} ({}/{})", error, delay, attempt, self.retries);
+            std::thread::sleep(delay);
+        }
+    }
+
+    pub fn fetch_input(&self, date: &AocDate) -> Result<String> {
+        let url = self.url(date, "/input");
+
+        Ok(self.send(|| self.http.get(&url), true)?)
+    }
+
+    /// Fetches the HTML page describing a puzzle, which includes part 2 once part 1 is solved.
+    pub fn fetch_description(&self, date: &AocDate) -> Result<String> {
+        let url = self.url(date, "");
+
+        Ok(self.send(|| self.http.get(&url), true)?)
+    }
+
+    pub fn submit(&self, date: &AocDate, part: Part, answer: &str) -> Result<Outcome> {
+        let url = self.url(date, "/answer");
+        let level = u8::from(part).to_string();
+        let html = self.send(|| self.http.post(&url).form(&[("level", level.as_str()), ("answer", answer)]), false)?;
+
+        Outcome::from_html(&html)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockServer;
+
+    fn test_options() -> ClientOptions {
+        ClientOptions { user_agent: "aoc-rs tests".to_string(), retry_delay: Duration::from_millis(1), ..Default::default() }
+    }
+
+    #[test]
+    pub fn test_outcome_parsing() {
+        let page = |text: &str| format!("<html><body><main><article><p>{}</p></article></main></body></html>", text);
+
+        assert_eq!(Outcome::from_html(&page("That's the right answer!  You are one gold star closer.")).unwrap(), Outcome::Correct);
+        assert_eq!(Outcome::from_html(&page("That's not the right answer; your answer is too high.")).unwrap(), Outcome::TooHigh);
+        assert_eq!(Outcome::from_html(&page("That's not the right answer; your answer is too low.")).unwrap(), Outcome::TooLow);
+        assert_eq!(Outcome::from_html(&page("That's not the right answer.  If you're stuck, ...")).unwrap(), Outcome::Wrong);
+        assert_eq!(
+            Outcome::from_html(&page("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 23s left to wait.")).unwrap(),
+            Outcome::RateLimited(Duration::from_secs(83))
+        );
+        assert_eq!(
+            Outcome::from_html(&page("You don't seem to be solving the right level.  Did you already complete it?")).unwrap(),
+            Outcome::AlreadySolved
+        );
+        assert!(Outcome::from_html(&page("Something else")).is_err());
+    }
+
+    #[test]
+    pub fn test_submit_against_mock_server() {
+        let server = MockServer::start(vec![(200, "<article><p>That's the right answer!</p></article>".to_string())]);
+        let client = AocClient::with_base_url(&server.url(), "secret", test_options()).unwrap();
+
+        let outcome = client.submit(&AocDate { year: 2021, day: 7 }, Part::Two, "168").unwrap();
+        assert_eq!(outcome, Outcome::Correct);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("POST /2021/day/7/answer HTTP/1.1"));
+        assert!(requests[0].to_lowercase().contains("cookie: session=secret"));
+        assert!(requests[0].ends_with("level=2&answer=168"));
+    }
+
+    #[test]
+    pub fn test_fetch_input_against_mock_server() {
+        let server = MockServer::start(vec![(200, "1\n2\n3\n".to_string())]);
+        let client = AocClient::with_base_url(&server.url(), "secret", test_options()).unwrap();
+
+        assert_eq!(client.fetch_input(&AocDate { year: 2021, day: 1 }).unwrap(), "1\n2\n3\n");
+
+        let request = &server.requests()[0];
+        assert!(request.starts_with("GET /2021/day/1/input HTTP/1.1"));
+        assert!(request.to_lowercase().contains("user-agent: aoc-rs tests"));
+    }
+
+    #[test]
+    pub fn test_fetch_description_against_mock_server() {
+        let server = MockServer::start(vec![(200, "<main><article></article></main>".to_string())]);
+        let client = AocClient::with_base_url(&server.url(), "secret", test_options()).unwrap();
+
+        assert_eq!(client.fetch_description(&AocDate { year: 2021, day: 9 }).unwrap(), "<main><article></article></main>");
+        assert!(server.requests()[0].starts_with("GET /2021/day/9 HTTP/1.1"));
+    }
+
+    #[test]
+    pub fn test_fetch_retries_server_errors() {
+        let server = MockServer::start(vec![(500, String::new()), (503, String::new()), (200, "42".to_string())]);
+        let client = AocClient::with_base_url(&server.url(), "secret", test_options()).unwrap();
+
+        assert_eq!(client.fetch_input(&AocDate { year: 2021, day: 1 }).unwrap(), "42");
+        assert_eq!(server.requests().len(), 3);
+
+        let server = MockServer::start(vec![(500, String::new()), (500, String::new())]);
+        let client = AocClient::with_base_url(&server.url(), "secret", ClientOptions { retries: 1, ..test_options() }).unwrap();
+        let error = client.fetch_input(&AocDate { year: 2021, day: 1 }).unwrap_err();
+
+        assert_eq!(error.downcast_ref::<FetchError>(), Some(&FetchError::Server(StatusCode::INTERNAL_SERVER_ERROR)));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    pub fn test_fetch_error_mapping() {
+        let server = MockServer::start(vec![(400, String::new()), (404, String::new()), (418, String::new()), (500, String::new())]);
+        let client = AocClient::with_base_url(&server.url(), "secret", ClientOptions { retries: 0, ..test_options() }).unwrap();
+        let date = AocDate { year: 2021, day: 1 };
+        let fetch_error = || client.fetch_input(&date).unwrap_err().downcast::<FetchError>().unwrap();
+
+        assert_eq!(fetch_error(), FetchError::SessionExpired);
+        assert_eq!(fetch_error(), FetchError::NotUnlocked);
+        assert_eq!(fetch_error(), FetchError::Unexpected(StatusCode::IM_A_TEAPOT));
+        assert_eq!(fetch_error(), FetchError::Server(StatusCode::INTERNAL_SERVER_ERROR));
+        assert_eq!(server.requests().len(), 4);
+    }
+
+    #[test]
+    pub fn test_submit_is_not_retried() {
+        let server = MockServer::start(vec![(502, String::new()), (200, String::new())]);
+        let client = AocClient::with_base_url(&server.url(), "secret", test_options()).unwrap();
+        let error = client.submit(&AocDate { year: 2021, day: 1 }, Part::One, "7").unwrap_err();
+
+        assert_eq!(error.downcast_ref::<FetchError>(), Some(&FetchError::Server(StatusCode::BAD_GATEWAY)));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    pub fn test_connection_failure() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let client = AocClient::with_base_url(&url, "secret", ClientOptions { retries: 1, ..test_options() }).unwrap();
+        let error = client.fetch_input(&AocDate { year: 2021, day: 1 }).unwrap_err();
+
+        assert!(matches!(error.downcast_ref::<FetchError>(), Some(FetchError::Transport(_))));
+    }
+}
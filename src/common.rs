@@ -1,20 +1,18 @@
-extern crate reqwest;
 extern crate chrono;
 
-use anyhow::{ Result, Context, anyhow };
-use reqwest::blocking::Client;
-use reqwest::header::COOKIE;
-use reqwest::StatusCode;
+use anyhow::{ Result, anyhow };
 use std::fmt;
-use std::cmp::{ min, max, PartialOrd, Ordering };
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::{BufReader, Read};
-use std::path::PathBuf;
-use log::{info, error, debug};
+use std::cmp::{ PartialOrd, Ordering };
+use std::str::FromStr;
+use std::time::{ Duration, Instant };
+use serde::{ Deserialize, Serialize };
 use chrono::prelude::*;
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+use crate::input::InputSource;
+
+pub mod parse;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct AocDate {
     pub year: u32,
     pub day: u32,
@@ -48,120 +46,556 @@ impl PartialOrd<AocDate> for AocDate {
     }
 }
 
+/// First year of Advent of Code.
+pub const FIRST_YEAR: u32 = 2015;
+
+/// Puzzles unlock at midnight US Eastern time, which is UTC-5 all December long.
+pub fn eastern() -> FixedOffset {
+    FixedOffset::west_opt(5 * 3600).unwrap()
+}
+
+/// Number of puzzles in an event: 25 until 2024, 12 since 2025.
+pub fn days_in_year(year: u32) -> u32 {
+    if year < 2025 { 25 } else { 12 }
+}
+
+/// FNV-1a hash of an input, identifying which input a run used without storing it.
+pub fn input_hash(input: &str) -> String {
+    let hash = input.bytes().fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+
+    format!("{:016x}", hash)
+}
+
+/// Formats a wait like `1d 2h 03m 04s`, omitting leading zero units.
+pub fn format_wait(wait: Duration) -> String {
+    let secs = wait.as_secs();
+    let (d, h, m, s) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+
+    if d > 0 {
+        format!("{}d {}h {:02}m {:02}s", d, h, m, s)
+    } else if h > 0 {
+        format!("{}h {:02}m {:02}s", h, m, s)
+    } else if m > 0 {
+        format!("{}m {:02}s", m, s)
+    } else {
+        format!("{}s", s)
+    }
+}
+
 impl AocDate {
+    /// The puzzle of the current day in US Eastern time, where the event is run.
     pub fn today() -> AocDate {
-        let dt = Local::now();
+        let dt = Utc::now().with_timezone(&eastern());
 
-        AocDate { year: dt.year() as u32, day: dt.day() as u32 }
+        AocDate { year: dt.year() as u32, day: dt.day() }
     }
 
     pub fn day(day: i32) -> AocDate {
-        AocDate { year: Local::now().year() as u32, day: min(max(1, day), 25) as u32 }     
+        let year = Utc::now().with_timezone(&eastern()).year() as u32;
+
+        AocDate { year, day: day.clamp(1, days_in_year(year) as i32) as u32 }
+    }
+
+    /// Checks that the date names an actual puzzle of some event.
+    pub fn validate(&self) -> Result<()> {
+        if self.year < FIRST_YEAR {
+            Err(anyhow!("There are no puzzles before {}", FIRST_YEAR))
+        } else if self.day < 1 || self.day > days_in_year(self.year) {
+            Err(anyhow!("{} is not a puzzle: {} has days 1 to {}", self, self.year, days_in_year(self.year)))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn unlock_time(&self) -> DateTime<Utc> {
+        let first_midnight = NaiveDate::from_ymd_opt(self.year as i32, 12, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        let midnight = first_midnight + chrono::Duration::days(self.day as i64 - 1);
+
+        eastern().from_local_datetime(&midnight).unwrap().with_timezone(&Utc)
     }
 
-    fn url(&self) -> String {
-        format!(
-            "https://adventofcode.com/{}/day/{}/input",
-            self.year, self.day
-        )
+    /// Time left until the puzzle unlocks, `None` if it is already available at `now`.
+    pub fn time_to_unlock(&self, now: DateTime<Utc>) -> Option<Duration> {
+        (self.unlock_time() - now).to_std().ok().filter(|d| !d.is_zero())
     }
 
-    fn file_path(&self) -> PathBuf {
-        let mut path_buf = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        path_buf.push("input");
-        path_buf.push(self.year.to_string());
-        path_buf.push(format!("day{}.txt", self.day));
+    /// Fails with the remaining time if the puzzle is still locked.
+    pub fn ensure_unlocked(&self) -> Result<()> {
+        match self.time_to_unlock(Utc::now()) {
+            None => Ok(()),
+            Some(wait) => Err(anyhow!(
+                "{} is not unlocked yet, {} left (unlocks at {})",
+                self, format_wait(wait), self.unlock_time().with_timezone(&eastern()).format("%Y-%m-%d %H:%M EST")
+            )),
+        }
+    }
+
+    pub fn load_data(&self, source: &dyn InputSource) -> Result<String> {
+        source.load(self)?.ok_or_else(|| anyhow!("No input available for {}", self))
+    }
+}
 
-        debug!("File for {}: {}", self.to_string(), path_buf.to_string_lossy());
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum Part {
+    One,
+    Two,
+}
 
-        path_buf
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl From<Part> for u8 {
+    fn from(part: Part) -> u8 {
+        match part {
+            Part::One => 1,
+            Part::Two => 2,
+        }
     }
+}
 
-    fn load_data(&self) -> Result<String> {
-        let input_path = self.file_path();
-        std::fs::create_dir_all(input_path.parent().unwrap())?;
-        let mut input = String::new();
+impl TryFrom<u8> for Part {
+    type Error = anyhow::Error;
 
-        if input_path.exists() {
-            info!("Reading input from {}", input_path.to_string_lossy());
-            let f = File::open(input_path).context("Cannot open input data file")?;
-            let mut reader = BufReader::new(f);
+    fn try_from(n: u8) -> Result<Self> {
+        n.to_string().parse()
+    }
+}
 
-            reader.read_to_string(&mut input).context("Unable to read input data file contents")?;
-        } else {
-            if let Some(token) = option_env!("AOC_TOKEN") {
-                let client = Client::new();
-                let token_header = format!("session={}", token);
-                let url = self.url();
-
-                let response = client
-                    .get(&url)
-                    .header(COOKIE, token_header)
-                    .send()?;
-
-                match response.status() {
-                    StatusCode::OK => {
-                        let body_text = response
-                        .text()
-                        .context("Unable to download input data file")?;
-
-                        let mut file = File::create(&input_path)?;
-                
-                        file.write_all(body_text.as_bytes())
-                        .context("Unable to store downloaded input data")?;
-
-                        input.replace_range(.., &body_text);
-                     },
-                    code =>
-                        return Err(anyhow!("Got unexpected response with code {}", code))
-                }
-
-           } else {
-                error!("Please, set your AoC token with AOC_TOKEN environment variable");
-                return Err(anyhow!("AoC token not set"));
-            }
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
         }
+    }
+}
 
-        Ok(input)
+impl FromStr for Part {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(anyhow!("Unknown puzzle part '{}', expected 1 or 2", s)),
+        }
     }
 }
 
+/// Which parts of a puzzle to run.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Parts {
+    One,
+    Two,
+    Both,
+}
+
+impl Parts {
+    pub fn to_vec(self) -> Vec<Part> {
+        match self {
+            Parts::One => vec![Part::One],
+            Parts::Two => vec![Part::Two],
+            Parts::Both => Part::ALL.to_vec(),
+        }
+    }
+
+    /// The selected part, unless both are selected.
+    pub fn single(self) -> Option<Part> {
+        match self {
+            Parts::One => Some(Part::One),
+            Parts::Two => Some(Part::Two),
+            Parts::Both => None,
+        }
+    }
+}
+
+impl From<Part> for Parts {
+    fn from(part: Part) -> Self {
+        match part {
+            Part::One => Parts::One,
+            Part::Two => Parts::Two,
+        }
+    }
+}
+
+impl fmt::Display for Parts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.single() {
+            Some(part) => write!(f, "{}", part),
+            None => write!(f, "both"),
+        }
+    }
+}
+
+impl FromStr for Parts {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "both" => Ok(Parts::Both),
+            _ => s.parse::<Part>().map(Parts::from).map_err(|_| anyhow!("Unknown puzzle part '{}', expected 1, 2 or both", s)),
+        }
+    }
+}
+
+/// A puzzle solver. The input is parsed once into `Parsed`, which both parts then
+/// share; days without a parsing step implement [`TextSolution`] instead. The two
+/// parts may answer with different types, e.g. a count for part 1 and some rendered
+/// text for part 2.
 pub trait Solution {
-    type Output: fmt::Display;
+    type Parsed;
+    type Output1: fmt::Display;
+    type Output2: fmt::Display;
+
+    /// Whether `part1` actually solves the puzzle; stubs set it to `false`.
+    const PART1_IMPLEMENTED: bool = true;
+    /// Whether `part2` actually solves the puzzle; stubs set it to `false`.
+    const PART2_IMPLEMENTED: bool = true;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed>;
+    fn part1(&self, input: &Self::Parsed) -> Result<Self::Output1>;
+    fn part2(&self, input: &Self::Parsed) -> Result<Self::Output2>;
 
-    fn part1(&self, input: &str) -> Result<Self::Output>;
-    fn part2(&self, input: &str) -> Result<Self::Output>;
+    /// Solves both parts in a single pass, for days whose part 2 continues from
+    /// the state part 1 ends with. `None` means the parts are solved separately.
+    fn solve_both(&self, _input: &Self::Parsed) -> Option<Result<(Self::Output1, Self::Output2)>> {
+        None
+    }
+}
+
+/// A puzzle solver working on the input text itself, for days with nothing worth
+/// parsing up front. Each one is a `Solution` whose parsed input is the text.
+pub trait TextSolution {
+    type Output1: fmt::Display;
+    type Output2: fmt::Display;
+
+    const PART1_IMPLEMENTED: bool = true;
+    const PART2_IMPLEMENTED: bool = true;
+
+    fn part1(&self, input: &str) -> Result<Self::Output1>;
+    fn part2(&self, input: &str) -> Result<Self::Output2>;
+}
+
+impl<T: TextSolution> Solution for T {
+    type Parsed = String;
+    type Output1 = T::Output1;
+    type Output2 = T::Output2;
+
+    const PART1_IMPLEMENTED: bool = T::PART1_IMPLEMENTED;
+    const PART2_IMPLEMENTED: bool = T::PART2_IMPLEMENTED;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed> {
+        Ok(input.to_string())
+    }
+
+    fn part1(&self, input: &Self::Parsed) -> Result<Self::Output1> {
+        TextSolution::part1(self, input)
+    }
+
+    fn part2(&self, input: &Self::Parsed) -> Result<Self::Output2> {
+        TextSolution::part2(self, input)
+    }
+}
+
+pub struct PartReport {
+    pub part: Part,
+    pub answer: std::result::Result<String, String>,
+    pub elapsed: Duration,
+}
+
+pub struct RunReport {
+    pub date: AocDate,
+    /// Set when the puzzle could not be run at all, e.g. its input failed to load or parse.
+    pub error: Option<String>,
+    /// Hash of the input the puzzle was solved with, see [`input_hash`].
+    pub input_hash: Option<String>,
+    /// Time spent loading the input, `None` if the caller did not measure it.
+    pub load_elapsed: Option<Duration>,
+    /// Time spent parsing the input, `None` if parsing was not attempted.
+    pub parse_elapsed: Option<Duration>,
+    /// Both parts were solved in one pass, so each part reports the time of the whole pass.
+    pub combined: bool,
+    pub parts: Vec<PartReport>,
+}
+
+impl RunReport {
+    pub fn new(date: AocDate) -> Self {
+        RunReport {
+            date,
+            error: None,
+            input_hash: None,
+            load_elapsed: None,
+            parse_elapsed: None,
+            combined: false,
+            parts: Vec::new(),
+        }
+    }
+
+    pub fn failed(date: AocDate, error: &anyhow::Error) -> Self {
+        RunReport { error: Some(format!("{:#}", error)), ..RunReport::new(date) }
+    }
+
+    pub fn part(&self, part: Part) -> Option<&PartReport> {
+        self.parts.iter().find(|p| p.part == part)
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none() && self.parts.iter().all(|p| p.answer.is_ok())
+    }
 }
 
 pub trait Solvable {
     fn get_date(&self) -> AocDate;
-    fn solve(&self);
+    fn implemented(&self, part: Part) -> bool;
+
+    /// Parses the input and solves a single part.
+    fn run(&self, part: Part, input: &str) -> Result<String>;
+
+    /// Parses the input once and returns a solver of single parts working on the
+    /// parsed value, so parts can be solved repeatedly without parsing again.
+    fn prepare<'a>(&'a self, input: &str) -> Result<Box<dyn Fn(Part) -> Result<String> + 'a>>;
+
+    /// Parses the input once and solves the requested parts, timing parsing and
+    /// each part separately. The parts run independently, a failing part 1 does
+    /// not prevent part 2 from running. When both parts are requested and the day
+    /// can solve them in one pass, that pass is used instead.
+    fn solve(&self, input: &str, parts: Parts) -> RunReport;
+}
+
+impl<const Y: u32, const D: u32> Day<Y, D> where Day<Y,D>: Solution {
+    fn answer(&self, part: Part, parsed: &<Self as Solution>::Parsed) -> Result<String> {
+        match part {
+            Part::One => self.part1(parsed).map(|r| r.to_string()),
+            Part::Two => self.part2(parsed).map(|r| r.to_string()),
+        }
+    }
 }
 
 impl<const Y: u32, const D: u32> Solvable for Day<Y, D> where Day<Y,D>: Solution {
     fn get_date(&self) -> AocDate {
         self.date
     }
-    
-    fn solve(&self) {
-        let input = self.date.load_data().unwrap_or_else(|e| panic!("Unable to load input data: {}", e));
-
-        match self.part1(&input) {
-            Err(error) => error!("Error solving {} part 1: {}", self.date, error),
-            Ok(result1) => {
-                info!("{} part 1: {}", self.date, result1);
-                match self.part2(&input) {
-                    Err(error) => error!("Error solving {} part 2: {}", self.date, error),
-                    Ok(result2) => info!("{} part 2: {}", self.date, result2),
-                }
+
+    fn implemented(&self, part: Part) -> bool {
+        match part {
+            Part::One => Self::PART1_IMPLEMENTED,
+            Part::Two => Self::PART2_IMPLEMENTED,
+        }
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<String> {
+        self.answer(part, &self.parse(input)?)
+    }
+
+    fn prepare<'a>(&'a self, input: &str) -> Result<Box<dyn Fn(Part) -> Result<String> + 'a>> {
+        let parsed = self.parse(input)?;
+
+        Ok(Box::new(move |part| self.answer(part, &parsed)))
+    }
+
+    fn solve(&self, input: &str, parts: Parts) -> RunReport {
+        let mut report = RunReport { input_hash: Some(input_hash(input)), ..RunReport::new(self.date) };
+
+        let start = Instant::now();
+        let parsed = self.parse(input);
+        report.parse_elapsed = Some(start.elapsed());
+
+        let parsed = match parsed {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                report.error = Some(format!("Unable to parse input: {:#}", e));
+                return report;
+            },
+        };
+
+        if parts == Parts::Both {
+            let start = Instant::now();
+
+            if let Some(answers) = self.solve_both(&parsed) {
+                let elapsed = start.elapsed();
+                let (answer1, answer2) = match answers {
+                    Ok((a1, a2)) => (Ok(a1.to_string()), Ok(a2.to_string())),
+                    Err(e) => (Err(format!("{:#}", e)), Err(format!("{:#}", e))),
+                };
+
+                report.combined = true;
+                report.parts.push(PartReport { part: Part::One, answer: answer1, elapsed });
+                report.parts.push(PartReport { part: Part::Two, answer: answer2, elapsed });
+
+                return report;
             }
         }
+
+        for p in parts.to_vec() {
+            let start = Instant::now();
+            let answer = self.answer(p, &parsed).map_err(|e| format!("{:#}", e));
+
+            report.parts.push(PartReport { part: p, answer, elapsed: start.elapsed() });
+        }
+
+        report
     }
 }
 
+impl<const Y: u32, const D: u32> Day<Y, D> {
+    pub const DATE: AocDate = AocDate { year: Y, day: D };
+}
+
 impl<const Y: u32, const D: u32> Default for Day<Y, D> {
     fn default() -> Self {
-        Day { date: AocDate { year: Y, day: D } }
+        Day { date: Self::DATE }
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_unlock_time() {
+        let date = AocDate { year: 2021, day: 1 };
+        assert_eq!(date.unlock_time(), Utc.ymd(2021, 12, 1).and_hms(5, 0, 0));
+        assert_eq!(AocDate { year: 2021, day: 25 }.unlock_time(), Utc.ymd(2021, 12, 25).and_hms(5, 0, 0));
+
+        let now = Utc.ymd(2021, 12, 1).and_hms(3, 58, 30);
+        assert_eq!(date.time_to_unlock(now), Some(Duration::from_secs(3690)));
+        assert_eq!(date.time_to_unlock(date.unlock_time()), None);
+        assert_eq!(date.time_to_unlock(Utc.ymd(2022, 1, 1).and_hms(0, 0, 0)), None);
+        assert!(AocDate { year: 2015, day: 1 }.ensure_unlocked().is_ok());
+    }
+
+    #[test]
+    pub fn test_validate() {
+        assert_eq!(days_in_year(2024), 25);
+        assert_eq!(days_in_year(2025), 12);
+
+        assert!(AocDate { year: 2021, day: 25 }.validate().is_ok());
+        assert!(AocDate { year: 2025, day: 12 }.validate().is_ok());
+        assert!(AocDate { year: 2025, day: 13 }.validate().is_err());
+        assert!(AocDate { year: 2021, day: 0 }.validate().is_err());
+        assert!(AocDate { year: 2014, day: 1 }.validate().is_err());
+    }
+
+    impl Solution for Day<2015, 25> {
+        type Parsed = Vec<u64>;
+        type Output1 = u64;
+        type Output2 = String;
+
+        fn parse(&self, input: &str) -> Result<Self::Parsed> {
+            input.split(',').map(|s| s.trim().parse().map_err(anyhow::Error::from)).collect()
+        }
+
+        fn part1(&self, input: &Self::Parsed) -> Result<Self::Output1> {
+            Ok(input.iter().sum())
+        }
+
+        fn part2(&self, input: &Self::Parsed) -> Result<Self::Output2> {
+            Ok(input.iter().map(|n| n.to_string()).collect::<Vec<_>>().join("+"))
+        }
+    }
+
+    #[test]
+    pub fn test_solve_report() {
+        let solution = Day::<2015, 25>::default();
+        let report = solution.solve("1, 2,3", Parts::Both);
+
+        assert!(report.is_ok());
+        assert!(report.parse_elapsed.is_some());
+        assert_eq!(report.part(Part::One).unwrap().answer, Ok("6".to_string()));
+        assert_eq!(report.part(Part::Two).unwrap().answer, Ok("1+2+3".to_string()));
+        assert_eq!(solution.run(Part::Two, "4,5").unwrap(), "4+5");
+
+        assert!(!report.combined);
+        assert_eq!(report.input_hash, Some(input_hash("1, 2,3")));
+
+        let report = solution.solve("1,x", Parts::One);
+        assert!(report.error.unwrap().starts_with("Unable to parse input"));
+        assert!(report.parts.is_empty());
+    }
+
+    impl Solution for Day<2015, 24> {
+        type Parsed = u64;
+        type Output1 = u64;
+        type Output2 = u64;
+
+        fn parse(&self, input: &str) -> Result<Self::Parsed> {
+            Ok(input.trim().parse()?)
+        }
+
+        fn part1(&self, input: &Self::Parsed) -> Result<Self::Output1> {
+            Ok(input * 2)
+        }
+
+        fn part2(&self, input: &Self::Parsed) -> Result<Self::Output2> {
+            Ok(input * 4)
+        }
+
+        fn solve_both(&self, input: &Self::Parsed) -> Option<Result<(Self::Output1, Self::Output2)>> {
+            let doubled = input * 2;
+
+            Some(Ok((doubled, doubled * 2)))
+        }
+    }
+
+    #[test]
+    pub fn test_solve_both() {
+        let solution = Day::<2015, 24>::default();
+        let report = solution.solve("3", Parts::Both);
+
+        assert!(report.combined);
+        assert_eq!(report.part(Part::One).unwrap().answer, Ok("6".to_string()));
+        assert_eq!(report.part(Part::Two).unwrap().answer, Ok("12".to_string()));
+
+        let report = solution.solve("3", Parts::Two);
+        assert!(!report.combined);
+        assert_eq!(report.parts.len(), 1);
+        assert_eq!(report.part(Part::Two).unwrap().answer, Ok("12".to_string()));
+    }
+
+    #[test]
+    pub fn test_input_hash() {
+        assert_eq!(input_hash(""), "cbf29ce484222325");
+        assert_eq!(input_hash("a"), "af63dc4c8601ec8c");
+        assert_ne!(input_hash("3,4,3,1,2"), input_hash("3,4,3,1,2\n"));
+    }
+
+    impl TextSolution for Day<2015, 23> {
+        type Output1 = String;
+        type Output2 = usize;
+
+        const PART1_IMPLEMENTED: bool = false;
+
+        fn part1(&self, _input: &str) -> Result<Self::Output1> {
+            Err(anyhow!("Not implemented"))
+        }
+
+        fn part2(&self, input: &str) -> Result<Self::Output2> {
+            Ok(input.len())
+        }
+    }
+
+    #[test]
+    pub fn test_independent_parts() {
+        let report = Day::<2015, 23>::default().solve("abcd", Parts::Both);
+
+        assert_eq!(report.part(Part::One).unwrap().answer, Err("Not implemented".to_string()));
+        assert_eq!(report.part(Part::Two).unwrap().answer, Ok("4".to_string()));
+        assert!(!report.is_ok());
+        assert!(!Day::<2015, 23>::default().implemented(Part::One));
+
+        assert_eq!("both".parse::<Parts>().unwrap(), Parts::Both);
+        assert_eq!("2".parse::<Parts>().unwrap().to_vec(), vec![Part::Two]);
+        assert_eq!(Parts::Both.single(), None);
+        assert!("3".parse::<Parts>().is_err());
+    }
+
+    #[test]
+    pub fn test_format_wait() {
+        assert_eq!(format_wait(Duration::from_secs(7)), "7s");
+        assert_eq!(format_wait(Duration::from_secs(65)), "1m 05s");
+        assert_eq!(format_wait(Duration::from_secs(3690)), "1h 01m 30s");
+        assert_eq!(format_wait(Duration::from_secs(90061)), "1d 1h 01m 01s");
+    }
+}
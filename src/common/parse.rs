@@ -0,0 +1,404 @@
+// Parsing helpers for puzzle inputs. Errors carry the line (and column where it makes
+// sense) they come from, counting from 1, so a malformed input is reported as
+// "line 3: column 5: invalid value 'x'" instead of a panic.
+
+use anyhow::{ Result, Context, anyhow };
+use regex::{ Captures, Regex };
+use std::fmt::{ self, Display };
+use std::str::FromStr;
+
+use crate::grid::Grid;
+
+/// Column of a value within its line, counting from 1, given as the context of its
+/// parse error. Being typed rather than text, it can be moved back by a [`Block`] to
+/// the column in the whole input.
+#[derive(Debug)]
+struct Column {
+    column: usize,
+    group: Option<String>,
+}
+
+impl Column {
+    fn new(column: usize) -> Self {
+        Column { column, group: None }
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.group {
+            Some(group) => write!(f, "column {} ({})", self.column, group),
+            None => write!(f, "column {}", self.column),
+        }
+    }
+}
+
+/// Parses a single value, naming the text that could not be parsed.
+pub fn value<T>(s: &str) -> Result<T> where T: FromStr, T::Err: Display {
+    s.parse::<T>().map_err(|e| anyhow!("invalid value '{}': {}", s, e))
+}
+
+/// Parses every non-blank line with `f`, trimmed.
+pub fn lines<'a, T>(input: &'a str, mut f: impl FnMut(&'a str) -> Result<T>) -> Result<Vec<T>> {
+    input.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(n, line)| f(line).with_context(|| format!("line {}", n)))
+        .collect()
+}
+
+/// One value per line, like a list of numbers.
+pub fn values<T>(input: &str) -> Result<Vec<T>> where T: FromStr, T::Err: Display {
+    lines(input, value)
+}
+
+/// Values separated by `separator` on a single line, such as `3,4,3,1,2`.
+pub fn separated<T>(line: &str, separator: char) -> Result<Vec<T>> where T: FromStr, T::Err: Display {
+    let mut column = 1;
+
+    line.trim_end().split(separator)
+        .map(|item| {
+            let start = column + item.chars().count() - item.trim_start().chars().count();
+            column += item.chars().count() + 1;
+
+            value(item.trim()).context(Column::new(start))
+        })
+        .collect()
+}
+
+/// Values separated by any amount of whitespace on a single line, such as `22 13 17 11  0`.
+pub fn whitespace_separated<T>(line: &str) -> Result<Vec<T>> where T: FromStr, T::Err: Display {
+    line.split_whitespace()
+        .map(|item| {
+            let column = line[..item.as_ptr() as usize - line.as_ptr() as usize].chars().count() + 1;
+
+            value(item).context(Column::new(column))
+        })
+        .collect()
+}
+
+/// A run of non-blank lines of the input, without their common indentation
+/// and trailing whitespace.
+pub struct Block<'a> {
+    /// Line number of the first line
+    pub line: usize,
+    pub lines: Vec<&'a str>,
+    /// Number of characters of indentation stripped from each line
+    indent: usize,
+}
+
+impl<'a> Block<'a> {
+    fn new(line: usize, raw: &[&'a str]) -> Self {
+        // Common leading whitespace, compared by character as lines may mix kinds of spaces.
+        let indent = raw.iter()
+            .map(|l| &l[..l.len() - l.trim_start().len()])
+            .reduce(|common, lead| {
+                let end = common.char_indices()
+                    .zip(lead.chars())
+                    .find(|((_, a), b)| a != b)
+                    .map_or(common.len().min(lead.len()), |((i, _), _)| i);
+
+                &common[..end]
+            })
+            .unwrap_or("");
+
+        Block {
+            line,
+            lines: raw.iter().map(|l| l.strip_prefix(indent).unwrap_or(l).trim_end()).collect(),
+            indent: indent.chars().count(),
+        }
+    }
+
+    /// Error on the `i`th line of the block, located in the whole input: with the line
+    /// number, and the column moved back past the stripped indentation.
+    fn locate(&self, i: usize, mut error: anyhow::Error) -> anyhow::Error {
+        if let Some(column) = error.downcast_mut::<Column>() {
+            column.column += self.indent;
+        }
+
+        error.context(format!("line {}", self.line + i))
+    }
+
+    /// Parses every line of the block with `f`, errors naming the line in the whole input.
+    pub fn parse<T>(&self, mut f: impl FnMut(&'a str) -> Result<T>) -> Result<Vec<T>> {
+        self.lines.iter()
+            .enumerate()
+            .map(|(i, line)| f(line).map_err(|e| self.locate(i, e)))
+            .collect()
+    }
+
+    /// Parses a block made of a single line, like a header.
+    pub fn single<T>(&self, f: impl FnOnce(&'a str) -> Result<T>) -> Result<T> {
+        match self.lines[..] {
+            [line] => f(line).map_err(|e| self.locate(0, e)),
+            _ => Err(anyhow!("expected a single line at line {}, got {}", self.line, self.lines.len())),
+        }
+    }
+
+    /// The block as a map, converting each character with `cell`.
+    #[allow(dead_code)]
+    pub fn grid<T>(&self, cell: impl Fn(char) -> Result<T>) -> Result<Grid<T>> {
+        Grid::parse(&self.lines.join("\n"), cell).with_context(|| format!("block at line {}", self.line))
+    }
+}
+
+/// Splits the input at blank lines, lines holding only whitespace included.
+pub fn blocks(input: &str) -> Vec<Block<'_>> {
+    let lines: Vec<&str> = input.lines().collect();
+    let mut blocks = Vec::new();
+    let mut start = None;
+
+    for i in 0..=lines.len() {
+        let blank = lines.get(i).is_none_or(|l| l.trim().is_empty());
+
+        match (blank, start) {
+            (true, Some(first)) => {
+                blocks.push(Block::new(first + 1, &lines[first..i]));
+                start = None;
+            },
+            (false, None) => start = Some(i),
+            _ => (),
+        }
+    }
+
+    blocks
+}
+
+/// Input made of a header block followed by blank-line separated sections,
+/// like draws followed by bingo boards.
+pub struct Sections<'a> {
+    pub header: Block<'a>,
+    pub blocks: Vec<Block<'a>>,
+}
+
+impl<'a> Sections<'a> {
+    pub fn parse(input: &'a str) -> Result<Self> {
+        let mut blocks = blocks(input);
+
+        if blocks.is_empty() {
+            return Err(anyhow!("empty input"));
+        }
+
+        Ok(Sections { header: blocks.remove(0), blocks })
+    }
+}
+
+/// Matches a whole line against `pattern`.
+pub fn captures<'a>(pattern: &Regex, s: &'a str) -> Result<Captures<'a>> {
+    pattern.captures(s).ok_or_else(|| anyhow!("'{}' doesn't match {}", s, pattern))
+}
+
+/// Parses a numbered capture group.
+#[allow(dead_code)]
+pub fn field<T>(caps: &Captures, group: usize) -> Result<T> where T: FromStr, T::Err: Display {
+    let m = caps.get(group).ok_or_else(|| anyhow!("group {} is missing", group))?;
+
+    value(m.as_str()).context(Column::new(m.start() + 1))
+}
+
+/// Parses a named capture group.
+pub fn named<T>(caps: &Captures, name: &str) -> Result<T> where T: FromStr, T::Err: Display {
+    let m = caps.name(name).ok_or_else(|| anyhow!("group '{}' is missing", name))?;
+
+    value(m.as_str()).with_context(|| Column { column: m.start() + 1, group: Some(name.to_string()) })
+}
+
+/// Declares a struct, or an enum, along with a `FromStr` implementation matching
+/// a line against a regex and parsing each field from the named group of the same
+/// name with the field type's own `FromStr`. Enum variants each have their own
+/// regex, tried in order; their fields are named after the groups too.
+///
+/// ```ignore
+/// regex_record! {
+///     #[derive(Debug)]
+///     pub struct Move = r"^move (?P<count>\d+) from (?P<from>\w+)$" {
+///         count: usize,
+///         from: String,
+///     }
+/// }
+///
+/// regex_record! {
+///     pub enum Instruction {
+///         Noop = r"^noop$",
+///         Add(value: i32) = r"^add (?P<value>-?\d+)$",
+///         Jump { offset: i32, target: String } = r"^jump (?P<offset>-?\d+) to (?P<target>\w+)$",
+///     }
+/// }
+/// ```
+macro_rules! regex_record {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident = $pattern:literal {
+            $( $field_vis:vis $field:ident : $type:ty ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $( $field_vis $field: $type ),*
+        }
+
+        impl ::std::str::FromStr for $name {
+            type Err = ::anyhow::Error;
+
+            fn from_str(s: &str) -> ::anyhow::Result<Self> {
+                ::lazy_static::lazy_static! {
+                    static ref PATTERN: ::regex::Regex = ::regex::Regex::new($pattern).unwrap();
+                }
+
+                let caps = $crate::common::parse::captures(&PATTERN, s)?;
+
+                Ok($name {
+                    $( $field: $crate::common::parse::named(&caps, stringify!($field))? ),*
+                })
+            }
+        }
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $variant:ident
+                $( ( $( $group:ident : $group_type:ty ),* $(,)? ) )?
+                $( { $( $field:ident : $field_type:ty ),* $(,)? } )?
+                = $pattern:literal
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $(
+                $variant
+                $( ( $( $group_type ),* ) )?
+                $( { $( $field: $field_type ),* } )?
+            ),*
+        }
+
+        impl ::std::str::FromStr for $name {
+            type Err = ::anyhow::Error;
+
+            fn from_str(s: &str) -> ::anyhow::Result<Self> {
+                $({
+                    ::lazy_static::lazy_static! {
+                        static ref PATTERN: ::regex::Regex = ::regex::Regex::new($pattern).unwrap();
+                    }
+
+                    #[allow(unused_variables)]
+                    if let Some(caps) = PATTERN.captures(s) {
+                        return Ok($name::$variant
+                            $( ( $( $crate::common::parse::named(&caps, stringify!($group))? ),* ) )?
+                            $( { $( $field: $crate::common::parse::named(&caps, stringify!($field))? ),* } )?
+                        );
+                    }
+                })*
+
+                Err(::anyhow::anyhow!("'{}' doesn't match any {}", s, stringify!($name)))
+            }
+        }
+    };
+}
+
+pub(crate) use regex_record;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_line_values() {
+        assert_eq!(values::<i32>("199\n200\n\n-3\n").unwrap(), vec![199, 200, -3]);
+        assert_eq!(format!("{:#}", values::<i32>("1\n2\nx3").unwrap_err()), "line 3: invalid value 'x3': invalid digit found in string");
+
+        assert_eq!(separated::<u8>("3,4, 3,1,2\n", ',').unwrap(), vec![3, 4, 3, 1, 2]);
+        assert_eq!(format!("{:#}", separated::<u8>("3,4,-1", ',').unwrap_err()), "column 5: invalid value '-1': invalid digit found in string");
+        assert_eq!(format!("{:#}", separated::<u8>("1,\u{a0}2,\u{3000}x", ',').unwrap_err()), "column 7: invalid value 'x': invalid digit found in string");
+
+        assert_eq!(whitespace_separated::<u8>(" 22 13  0").unwrap(), vec![22, 13, 0]);
+        assert_eq!(format!("{:#}", whitespace_separated::<u8>("22 13  x").unwrap_err()), "column 8: invalid value 'x': invalid digit found in string");
+    }
+
+    #[test]
+    pub fn test_blocks() {
+        let blocks = blocks("7,4,9\n\n22 13\n 8  2\n\n\n1 2\n");
+
+        assert_eq!(blocks.iter().map(|b| (b.line, b.lines.len())).collect::<Vec<_>>(), vec![(1, 1), (3, 2), (7, 1)]);
+        assert_eq!(blocks[1].parse(whitespace_separated::<u8>).unwrap(), vec![vec![22, 13], vec![8, 2]]);
+        assert_eq!(format!("{:#}", blocks[2].parse(|l| separated::<u8>(l, ',')).unwrap_err()), "line 7: column 1: invalid value '1 2': invalid digit found in string");
+
+        let mixed = super::blocks("\u{3000}1\n  2\n\n\u{a0}  3 4\n\u{a0} 5\n");
+        assert_eq!(mixed[0].lines, vec!["\u{3000}1", "  2"]);
+        assert_eq!(mixed[1].lines, vec![" 3 4", "5"]);
+
+        let indented = super::blocks("\n  1,2\n  3,x\n\n\u{3000} 4\n\u{3000} 5,y\n");
+        assert_eq!(format!("{:#}", indented[0].parse(|l| separated::<u8>(l, ',')).unwrap_err()), "line 3: column 5: invalid value 'x': invalid digit found in string");
+        assert_eq!(format!("{:#}", indented[1].parse(|l| separated::<u8>(l, ',')).unwrap_err()), "line 6: column 5: invalid value 'y': invalid digit found in string");
+    }
+
+    #[test]
+    pub fn test_sections() {
+        let sections = Sections::parse("\n  7,4,9  \n \n    #.#\n     .# \n\t\n\n    12\n    34\n").unwrap();
+
+        assert_eq!(sections.header.single(|l| separated::<u8>(l, ',')).unwrap(), vec![7, 4, 9]);
+        assert_eq!(sections.blocks.len(), 2);
+        assert_eq!(sections.blocks[0].lines, vec!["#.#", " .#"]);
+        assert_eq!(sections.blocks[1].line, 8);
+        assert_eq!(sections.blocks[1].grid(|c| value::<u8>(&c.to_string())).unwrap().to_string(), "12\n34\n");
+        assert!(sections.blocks[0].single(Ok).is_err());
+        assert!(Sections::parse(" \n\n").is_err());
+
+        let error = sections.blocks[0].grid(|c| if c == '#' { Ok(true) } else { Err(anyhow!("no")) }).unwrap_err();
+        assert_eq!(format!("{:#}", error), "block at line 4: Invalid cell at row 1, column 2: no");
+    }
+
+    #[test]
+    pub fn test_captures() {
+        let pattern = Regex::new(r"^(?P<x>\d+),(\d+)$").unwrap();
+        let caps = captures(&pattern, "12,x4").map(|_| ()).unwrap_err();
+        assert_eq!(caps.to_string(), r"'12,x4' doesn't match ^(?P<x>\d+),(\d+)$");
+
+        let caps = captures(&pattern, "12,345").unwrap();
+        assert_eq!(named::<u32>(&caps, "x").unwrap(), 12);
+        assert_eq!(field::<u32>(&caps, 2).unwrap(), 345);
+        assert_eq!(format!("{:#}", field::<u8>(&caps, 2).unwrap_err()), "column 4: invalid value '345': number too large to fit in target type");
+        assert!(named::<u32>(&caps, "y").is_err());
+    }
+
+    regex_record! {
+        #[derive(Debug, PartialEq, Eq)]
+        struct Move = r"^move (?P<count>\d+) from (?P<from>\w+)$" {
+            count: u8,
+            from: String,
+        }
+    }
+
+    regex_record! {
+        #[derive(Debug, PartialEq, Eq)]
+        enum Instruction {
+            Noop = r"^noop$",
+            Add(value: i32) = r"^add (?P<value>-?\d+)$",
+            Jump { offset: i32, target: String } = r"^jump (?P<offset>-?\d+) to (?P<target>\w+)$",
+        }
+    }
+
+    #[test]
+    pub fn test_regex_records() {
+        assert_eq!("move 3 from a1".parse::<Move>().unwrap(), Move { count: 3, from: "a1".to_string() });
+        assert_eq!(
+            format!("{:#}", "move 300 from a1".parse::<Move>().unwrap_err()),
+            "column 6 (count): invalid value '300': number too large to fit in target type"
+        );
+        assert_eq!(
+            format!("{:#}", blocks("\n   move 300 from a1")[0].single(|l| l.parse::<Move>()).unwrap_err()),
+            "line 2: column 9 (count): invalid value '300': number too large to fit in target type"
+        );
+        assert!("move 3 to a1".parse::<Move>().is_err());
+
+        assert_eq!("noop".parse::<Instruction>().unwrap(), Instruction::Noop);
+        assert_eq!("add -7".parse::<Instruction>().unwrap(), Instruction::Add(-7));
+        assert_eq!(
+            "jump 2 to end".parse::<Instruction>().unwrap(),
+            Instruction::Jump { offset: 2, target: "end".to_string() }
+        );
+        assert_eq!("sub 1".parse::<Instruction>().unwrap_err().to_string(), "'sub 1' doesn't match any Instruction");
+    }
+}
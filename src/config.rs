@@ -0,0 +1,373 @@
+use anyhow::{ Result, Context, anyhow };
+use log::debug;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{ Path, PathBuf };
+
+use crate::client::{ AocClient, ClientOptions, DEFAULT_USER_AGENT };
+
+pub const TOKEN_VAR: &str = "AOC_TOKEN";
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+pub const DATA_DIR_VAR: &str = "AOC_DATA_DIR";
+pub const ACCOUNT_VAR: &str = "AOC_ACCOUNT";
+pub const USER_AGENT_VAR: &str = "AOC_USER_AGENT";
+pub const SESSION_FILE: &str = ".aoc-session";
+pub const DEFAULT_ACCOUNT: &str = "default";
+
+/// Contents of the optional `config.toml`.
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// AoC session cookie value
+    pub session: Option<String>,
+    /// Root of the input cache
+    pub input_dir: Option<PathBuf>,
+    /// Root of the recorded answers
+    pub data_dir: Option<PathBuf>,
+    /// Account whose inputs and answers are used
+    pub account: Option<String>,
+    /// User-Agent sent to the AoC site
+    pub user_agent: Option<String>,
+    /// Per-account settings, keyed by account name
+    #[serde(default)]
+    pub accounts: HashMap<String, AccountConfig>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountConfig {
+    /// AoC session cookie value of this account, preferred over the top-level one
+    pub session: Option<String>,
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/aoc-rs/config.toml`, falling back to `~/.config/aoc-rs/config.toml`.
+    pub fn default_path(env: &Env) -> Option<PathBuf> {
+        let base = env_var(env, "XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home_dir(env).map(|h| h.join(".config")))?;
+
+        Some(base.join("aoc-rs").join("config.toml"))
+    }
+
+    /// Reads a config file; a missing file yields the default configuration.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read config from {}", path.to_string_lossy()))?;
+
+        toml::from_str(&content)
+            .with_context(|| format!("Malformed config file {}", path.to_string_lossy()))
+    }
+}
+
+/// Looks up an environment variable. Settings are resolved through one so that
+/// tests don't depend on the environment they run in.
+pub type Env = dyn Fn(&str) -> Option<String>;
+
+/// The environment of the process.
+pub fn process_env(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+fn env_var(env: &Env, name: &str) -> Option<String> {
+    env(name).filter(|v| !v.trim().is_empty())
+}
+
+pub fn home_dir(env: &Env) -> Option<PathBuf> {
+    env_var(env, "HOME").map(PathBuf::from)
+}
+
+/// `$XDG_CACHE_HOME/aoc-rs`, falling back to `~/.cache/aoc-rs`.
+pub fn default_input_dir(env: &Env) -> PathBuf {
+    env_var(env, "XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home_dir(env).map(|h| h.join(".cache")))
+        .unwrap_or_else(std::env::temp_dir)
+        .join("aoc-rs")
+}
+
+/// `$XDG_DATA_HOME/aoc-rs`, falling back to `~/.local/share/aoc-rs`. Unlike the cache,
+/// what is kept there can't be downloaded again.
+pub fn default_data_dir(env: &Env) -> PathBuf {
+    env_var(env, "XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home_dir(env).map(|h| h.join(".local").join("share")))
+        .unwrap_or_else(std::env::temp_dir)
+        .join("aoc-rs")
+}
+
+/// First value set among the command line, the environment and the config file.
+fn first_of<T>(flag: Option<T>, env: Option<T>, config: Option<T>) -> Option<T> {
+    flag.or(env).or(config)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TokenSource {
+    Flag,
+    Environment,
+    SessionFile(PathBuf),
+    ConfigFile(PathBuf),
+}
+
+impl fmt::Display for TokenSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenSource::Flag => write!(f, "--token flag"),
+            TokenSource::Environment => write!(f, "{} environment variable", TOKEN_VAR),
+            TokenSource::SessionFile(path) => write!(f, "session file {}", path.to_string_lossy()),
+            TokenSource::ConfigFile(path) => write!(f, "config file {}", path.to_string_lossy()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Token {
+    pub value: String,
+    pub source: TokenSource,
+}
+
+/// Picks the session token from the first source that provides one, in order:
+/// command line flag, environment, session files (in the given order), then the config
+/// file, where the selected account's session is preferred over the top-level one.
+pub fn resolve_token(flag: Option<&str>, env: Option<String>, session_files: &[PathBuf], config: &Config, account: &str, config_path: Option<&Path>) -> Option<Token> {
+    let non_empty = |v: &str| {
+        let v = v.trim();
+        if v.is_empty() { None } else { Some(v.to_string()) }
+    };
+
+    let from_config = |value| Token {
+        value,
+        source: TokenSource::ConfigFile(config_path.map(Path::to_path_buf).unwrap_or_default()),
+    };
+
+    if let Some(value) = flag.and_then(non_empty) {
+        return Some(Token { value, source: TokenSource::Flag });
+    }
+
+    if let Some(value) = env.as_deref().and_then(non_empty) {
+        return Some(Token { value, source: TokenSource::Environment });
+    }
+
+    for path in session_files {
+        if let Some(value) = std::fs::read_to_string(path).ok().as_deref().and_then(non_empty) {
+            return Some(Token { value, source: TokenSource::SessionFile(path.clone()) });
+        }
+    }
+
+    config.accounts.get(account).and_then(|a| a.session.as_deref()).and_then(non_empty)
+        .or_else(|| config.session.as_deref().and_then(non_empty))
+        .map(from_config)
+}
+
+/// Command line options that override the environment and the config file.
+#[derive(Default)]
+pub struct Overrides {
+    pub token: Option<String>,
+    pub config: Option<PathBuf>,
+    pub input_dir: Option<PathBuf>,
+    pub data_dir: Option<PathBuf>,
+    pub account: Option<String>,
+}
+
+/// Runtime settings assembled from the command line, the environment and the config file.
+pub struct Settings {
+    pub token_flag: Option<String>,
+    /// Value of the token environment variable when the settings were loaded
+    pub token_env: Option<String>,
+    pub session_files: Vec<PathBuf>,
+    pub config: Config,
+    pub config_path: Option<PathBuf>,
+    /// Root of the input cache, holding one directory per account
+    pub input_dir: PathBuf,
+    /// Root of the recorded answers, holding one directory per account
+    pub data_dir: PathBuf,
+    pub account: String,
+    pub user_agent: String,
+}
+
+impl Settings {
+    pub fn load(overrides: Overrides) -> Result<Self> {
+        Settings::resolve(overrides, &process_env)
+    }
+
+    /// Settings from the command line, the environment seen through `env` and the config file.
+    pub fn resolve(overrides: Overrides, env: &Env) -> Result<Self> {
+        let config_path = overrides.config.or_else(|| Config::default_path(env));
+        let config = match &config_path {
+            Some(path) => Config::load(path)?,
+            None => Config::default(),
+        };
+
+        let input_dir = first_of(overrides.input_dir, env_var(env, INPUT_DIR_VAR).map(PathBuf::from), config.input_dir.clone())
+            .unwrap_or_else(|| default_input_dir(env));
+        let data_dir = first_of(overrides.data_dir, env_var(env, DATA_DIR_VAR).map(PathBuf::from), config.data_dir.clone())
+            .unwrap_or_else(|| default_data_dir(env));
+        let account = first_of(overrides.account, env_var(env, ACCOUNT_VAR), config.account.clone())
+            .unwrap_or_else(|| DEFAULT_ACCOUNT.to_string());
+
+        if account.is_empty() || account.contains(['/', '\\']) || account.starts_with('.') {
+            return Err(anyhow!("Invalid account name '{}'", account));
+        }
+
+        let user_agent = first_of(None, env_var(env, USER_AGENT_VAR), config.user_agent.clone())
+            .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string());
+
+        debug!("Input cache of account '{}': {}", account, input_dir.to_string_lossy());
+        debug!("Answers of account '{}': {}", account, data_dir.to_string_lossy());
+
+        Ok(Settings {
+            token_flag: overrides.token,
+            token_env: env(TOKEN_VAR),
+            session_files: Settings::session_files(env),
+            config,
+            config_path,
+            input_dir,
+            data_dir,
+            account,
+            user_agent,
+        })
+    }
+
+    /// Directory holding the cached inputs and puzzle descriptions of the selected account,
+    /// laid out as `<year>/day<N>.txt` and `<year>/day<N>.html`.
+    pub fn account_dir(&self) -> PathBuf {
+        self.input_dir.join(&self.account)
+    }
+
+    /// Directory holding the recorded answers of the selected account, laid out as `<year>/day<N>.toml`.
+    pub fn answers_dir(&self) -> PathBuf {
+        self.data_dir.join(&self.account)
+    }
+
+    /// Client for the AoC site, authenticated with the resolved session token.
+    pub fn client(&self) -> Result<AocClient> {
+        let options = ClientOptions { user_agent: self.user_agent.clone(), ..Default::default() };
+
+        AocClient::new(&self.token()?, options)
+    }
+
+    /// `.aoc-session` in the current directory, then in the home directory.
+    fn session_files(env: &Env) -> Vec<PathBuf> {
+        std::env::current_dir().ok().into_iter()
+            .chain(home_dir(env))
+            .map(|dir| dir.join(SESSION_FILE))
+            .collect()
+    }
+
+    pub fn token(&self) -> Result<String> {
+        let token = resolve_token(
+            self.token_flag.as_deref(),
+            self.token_env.clone(),
+            &self.session_files,
+            &self.config,
+            &self.account,
+            self.config_path.as_deref(),
+        );
+
+        // The error ends up in the report of every day that needed a download, so it
+        // carries the hint itself instead of logging it on each attempt.
+        match token {
+            Some(token) => {
+                debug!("Using AoC session token from {}", token.source);
+                Ok(token.value)
+            },
+            None => Err(anyhow!(
+                "AoC token not set, provide it with --token, the {} environment variable, a {} file or the config file",
+                TOKEN_VAR, SESSION_FILE
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_token_precedence() {
+        let dir = std::env::temp_dir().join(format!("aoc-rs-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let session_file = dir.join(SESSION_FILE);
+        let missing_file = dir.join("missing").join(SESSION_FILE);
+        std::fs::write(&session_file, "from-file\n").unwrap();
+
+        let config_path = dir.join("config.toml");
+        std::fs::write(&config_path, "session = \"from-config\"\n").unwrap();
+        let config = Config::load(&config_path).unwrap();
+        let files = vec![missing_file, session_file.clone()];
+
+        let token = |flag: Option<&str>, env: Option<&str>, files: &[PathBuf]|
+            resolve_token(flag, env.map(String::from), files, &config, DEFAULT_ACCOUNT, Some(&config_path));
+
+        assert_eq!(token(Some("from-flag"), Some("from-env"), &files).unwrap().source, TokenSource::Flag);
+        assert_eq!(token(None, Some("from-env"), &files).unwrap(), Token { value: "from-env".to_string(), source: TokenSource::Environment });
+        assert_eq!(token(None, Some(" "), &files).unwrap(), Token { value: "from-file".to_string(), source: TokenSource::SessionFile(session_file) });
+        assert_eq!(token(None, None, &[]).unwrap(), Token { value: "from-config".to_string(), source: TokenSource::ConfigFile(config_path.clone()) });
+        assert_eq!(resolve_token(None, None, &[], &Config::default(), DEFAULT_ACCOUNT, None), None);
+
+        assert_eq!(Config::load(&dir.join("absent.toml")).unwrap(), Config::default());
+        std::fs::write(&config_path, "session = \"shared\"\naccount = \"work\"\n\n[accounts.work]\nsession = \"work-token\"\n").unwrap();
+        let config = Config::load(&config_path).unwrap();
+        assert_eq!(config.account.as_deref(), Some("work"));
+        assert_eq!(resolve_token(None, None, &[], &config, "work", None).unwrap().value, "work-token");
+        assert_eq!(resolve_token(None, None, &[], &config, "home", None).unwrap().value, "shared");
+        assert_eq!(resolve_token(None, Some("from-env".to_string()), &files, &config, "work", None).unwrap().value, "from-env");
+        assert_eq!(resolve_token(None, None, &files, &config, "work", None).unwrap().value, "from-file");
+        assert_eq!(resolve_token(None, Some("from-env".to_string()), &files, &config, "home", None).unwrap().value, "from-env");
+        assert_eq!(resolve_token(Some("from-flag"), None, &[], &config, "work", None).unwrap().value, "from-flag");
+
+        std::fs::write(&config_path, "sesion = \"typo\"\n").unwrap();
+        assert!(Config::load(&config_path).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    pub fn test_settings_precedence() {
+        let dir = std::env::temp_dir().join(format!("aoc-rs-settings-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config_path = dir.join("config.toml");
+        std::fs::write(&config_path, "input_dir = \"/srv/aoc\"\naccount = \"work\"\n").unwrap();
+
+        let environment = |vars: &[(&str, &str)]| {
+            let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+            move |name: &str| vars.get(name).cloned()
+        };
+        let load = |overrides: Overrides, vars: &[(&str, &str)]| Settings::resolve(overrides, &environment(vars));
+        let with_config = || Overrides { config: Some(config_path.clone()), ..Default::default() };
+
+        let settings = load(with_config(), &[]).unwrap();
+        assert_eq!(settings.account_dir(), PathBuf::from("/srv/aoc/work"));
+        assert_eq!(settings.token_env, None);
+
+        let settings = load(Overrides { data_dir: Some(dir.join("data")), ..with_config() }, &[(DATA_DIR_VAR, "/tmp/data")]).unwrap();
+        assert_eq!(settings.answers_dir(), dir.join("data").join("work"));
+        assert_eq!(load(with_config(), &[(DATA_DIR_VAR, "/tmp/data")]).unwrap().answers_dir(), PathBuf::from("/tmp/data/work"));
+
+        let settings = load(with_config(), &[(INPUT_DIR_VAR, "/tmp/aoc"), (ACCOUNT_VAR, "home"), (TOKEN_VAR, "from-env")]).unwrap();
+        assert_eq!(settings.account_dir(), PathBuf::from("/tmp/aoc/home"));
+        assert_eq!(settings.token_env.as_deref(), Some("from-env"));
+
+        let settings = load(Overrides {
+            input_dir: Some(dir.join("inputs")),
+            account: Some("home".to_string()),
+            ..with_config()
+        }, &[(INPUT_DIR_VAR, "/tmp/aoc"), (ACCOUNT_VAR, "other")]).unwrap();
+        assert_eq!(settings.account_dir(), dir.join("inputs").join("home"));
+
+        let settings = load(Overrides::default(), &[("HOME", "/home/me"), (ACCOUNT_VAR, " ")]).unwrap();
+        assert_eq!(settings.config_path, Some(PathBuf::from("/home/me/.config/aoc-rs/config.toml")));
+        assert_eq!(settings.account_dir(), PathBuf::from("/home/me/.cache/aoc-rs/default"));
+        assert_eq!(settings.answers_dir(), PathBuf::from("/home/me/.local/share/aoc-rs/default"));
+        assert!(settings.session_files.contains(&PathBuf::from("/home/me").join(SESSION_FILE)));
+
+        assert!(load(Overrides { account: Some("../x".to_string()), ..with_config() }, &[]).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,160 @@
+use anyhow::{ Result, Context, anyhow };
+use std::collections::HashMap;
+use std::path::{ Path, PathBuf };
+
+use crate::answers::{ self, Answers, Verdict };
+use crate::common::{ AocDate, Part, Parts, Solvable };
+
+/// File holding the expected answers of a day's examples, one table per example:
+///
+/// ```toml
+/// [example]
+/// part1 = "4512"
+/// part2 = "1924"
+/// ```
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// Example inputs taken from puzzle descriptions, laid out as
+/// `examples/<year>/day<N>/<name>.txt` in the source tree.
+pub fn default_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("examples")
+}
+
+pub fn day_dir(root: &Path, date: &AocDate) -> PathBuf {
+    root.join(date.year.to_string()).join(format!("day{}", date.day))
+}
+
+pub struct Example {
+    pub name: String,
+    pub input: String,
+    /// Answers given in the puzzle description, a part is left out if the example doesn't apply to it
+    pub expected: Answers,
+}
+
+/// Loads the examples of a day ordered by name, none if the day has no example directory.
+pub fn load(root: &Path, date: &AocDate) -> Result<Vec<Example>> {
+    let dir = day_dir(root, date);
+
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let answers_path = dir.join(ANSWERS_FILE);
+    let mut expected: HashMap<String, Answers> = if answers_path.exists() {
+        let content = std::fs::read_to_string(&answers_path)
+            .with_context(|| format!("Unable to read {}", answers_path.to_string_lossy()))?;
+
+        toml::from_str(&content).with_context(|| format!("Malformed answers file {}", answers_path.to_string_lossy()))?
+    } else {
+        HashMap::new()
+    };
+
+    let mut examples = Vec::new();
+
+    for entry in std::fs::read_dir(&dir).with_context(|| format!("Unable to list {}", dir.to_string_lossy()))? {
+        let path = entry?.path();
+
+        if path.extension().is_none_or(|ext| ext != "txt") {
+            continue;
+        }
+
+        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+        let input = std::fs::read_to_string(&path)
+            .with_context(|| format!("Unable to read example {}", path.to_string_lossy()))?;
+
+        examples.push(Example { expected: expected.remove(&name).unwrap_or_default(), name, input });
+    }
+
+    if let Some(name) = expected.keys().next() {
+        return Err(anyhow!("{} has answers for '{}', but there is no {}.txt", answers_path.to_string_lossy(), name, name));
+    }
+
+    examples.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(examples)
+}
+
+/// Runs a solution on an example, every implemented part with an expected answer
+/// on its own. When both parts are checked and the day solves them in one pass,
+/// that pass is checked as well; only its failures are kept.
+pub fn check(solution: &dyn Solvable, example: &Example) -> Vec<(Part, Verdict)> {
+    let parts: Vec<Part> = Part::ALL.iter()
+        .copied()
+        .filter(|&part| solution.implemented(part) && example.expected.get(part).is_some())
+        .collect();
+    let verdict = |report, part| answers::check(&report, &example.expected).into_iter().find(|(p, _)| *p == part);
+
+    let mut verdicts: Vec<(Part, Verdict)> = parts.iter()
+        .filter_map(|&part| verdict(solution.solve(&example.input, part.into()), part))
+        .collect();
+
+    if parts.len() == 2 {
+        let report = solution.solve(&example.input, Parts::Both);
+
+        if !report.combined {
+            return verdicts;
+        }
+
+        verdicts.extend(
+            answers::check(&report, &example.expected).into_iter().filter(|(_, v)| *v != Verdict::Pass)
+        );
+    }
+
+    verdicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solutions;
+
+    #[test]
+    pub fn test_examples() {
+        let root = default_dir();
+        let mut checked = 0;
+        let mut failures = Vec::new();
+
+        for solution in solutions::all() {
+            let date = solution.get_date();
+
+            for example in load(&root, &date).unwrap() {
+                for (part, verdict) in check(solution, &example) {
+                    checked += 1;
+
+                    if verdict != Verdict::Pass {
+                        failures.push(format!("{} {} part {}: {}", date, example.name, part, verdict));
+                    }
+                }
+            }
+        }
+
+        assert!(checked > 0, "No examples found in {}", root.to_string_lossy());
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    #[test]
+    pub fn test_load_examples() {
+        let root = std::env::temp_dir().join(format!("aoc-rs-examples-{}", std::process::id()));
+        let date = AocDate { year: 2021, day: 6 };
+        let dir = day_dir(&root, &date);
+
+        assert!(load(&root, &date).unwrap().is_empty());
+
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("small.txt"), "3,4,3,1,2").unwrap();
+        std::fs::write(dir.join("big.txt"), "1").unwrap();
+        std::fs::write(dir.join("notes.md"), "ignored").unwrap();
+        std::fs::write(dir.join(ANSWERS_FILE), "[small]\npart1 = \"5934\"\n").unwrap();
+
+        let examples = load(&root, &date).unwrap();
+        assert_eq!(examples.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(), vec!["big", "small"]);
+        assert_eq!(examples[0].expected, Answers::default());
+        assert_eq!(examples[1].expected.get(Part::One), Some("5934"));
+        assert_eq!(examples[1].expected.get(Part::Two), None);
+
+        std::fs::write(dir.join(ANSWERS_FILE), "[missing]\npart1 = \"1\"\n").unwrap();
+        assert!(load(&root, &date).is_err());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,308 @@
+#![allow(dead_code, reason = "shared helpers for grid puzzles, some only used by upcoming days and the tests below")]
+
+use anyhow::{ Result, anyhow };
+use std::fmt;
+use std::ops::{ Index, IndexMut };
+
+/// Position of a cell as (row, column), counting from the top left corner.
+pub type Pos = (usize, usize);
+
+/// Row and column offsets of the 4 orthogonal neighbours, in reading order.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// Row and column offsets of all 8 neighbours, in reading order.
+pub const ADJACENT: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+/// Number of orthogonal moves between two positions.
+pub fn manhattan(a: Pos, b: Pos) -> usize {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+/// Rectangular 2D map stored row by row, as found in most grid puzzles.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    columns: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(rows: usize, columns: usize, cells: Vec<T>) -> Result<Self> {
+        if cells.len() != rows * columns {
+            return Err(anyhow!("{} cells don't make a {}x{} grid", cells.len(), rows, columns));
+        }
+
+        Ok(Grid { cells, rows, columns })
+    }
+
+    pub fn filled(rows: usize, columns: usize, value: T) -> Self where T: Clone {
+        Grid { cells: vec![value; rows * columns], rows, columns }
+    }
+
+    /// Parses a map with one line per row, converting each character with `cell`.
+    /// Blank lines are skipped, but spaces are cells like any other character; all rows
+    /// must have the same width.
+    pub fn parse(data: &str, cell: impl Fn(char) -> Result<T>) -> Result<Self> {
+        let mut cells = Vec::new();
+        let mut rows = 0;
+        let mut columns = 0;
+
+        for line in data.lines().filter(|l| !l.trim().is_empty()) {
+            let width = line.chars().count();
+
+            if rows == 0 {
+                columns = width;
+            } else if width != columns {
+                return Err(anyhow!("Row {} has {} cells, expected {}", rows + 1, width, columns));
+            }
+
+            for (column, c) in line.chars().enumerate() {
+                cells.push(cell(c).map_err(|e| anyhow!("Invalid cell at row {}, column {}: {}", rows + 1, column + 1, e))?);
+            }
+            rows += 1;
+        }
+
+        Ok(Grid { cells, rows, columns })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, (row, column): Pos) -> bool {
+        row < self.rows && column < self.columns
+    }
+
+    /// Index of a position in the flat, row by row, cell storage.
+    pub fn index(&self, (row, column): Pos) -> usize {
+        row * self.columns + column
+    }
+
+    pub fn pos(&self, index: usize) -> Pos {
+        (index / self.columns, index % self.columns)
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        if self.contains(pos) { self.cells.get(self.index(pos)) } else { None }
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.contains(pos) {
+            let index = self.index(pos);
+            self.cells.get_mut(index)
+        } else {
+            None
+        }
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn cells_mut(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    /// The position one `(row, column)` step away, if it is inside the grid.
+    pub fn step(&self, (row, column): Pos, (dr, dc): (isize, isize)) -> Option<Pos> {
+        let pos = (row.checked_add_signed(dr)?, column.checked_add_signed(dc)?);
+
+        if self.contains(pos) { Some(pos) } else { None }
+    }
+
+    /// Up to 4 orthogonal neighbours of a position, in reading order.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ORTHOGONAL.iter().filter_map(move |&d| self.step(pos, d))
+    }
+
+    /// Up to 8 neighbours of a position, diagonals included, in reading order.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ADJACENT.iter().filter_map(move |&d| self.step(pos, d))
+    }
+
+    /// Every position in reading order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let columns = self.columns;
+
+        (0..self.cells.len()).map(move |i| (i / columns, i % columns))
+    }
+
+    /// Every cell along with its position, in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.columns..(row + 1) * self.columns]
+    }
+
+    pub fn column(&self, column: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(column).step_by(self.columns)
+    }
+
+    /// Positions from `start` (included) repeatedly moving by `direction` until leaving the grid.
+    pub fn ray(&self, start: Pos, direction: (isize, isize)) -> impl Iterator<Item = Pos> + '_ {
+        std::iter::successors(Some(start).filter(|&p| self.contains(p)), move |&p| self.step(p, direction))
+    }
+
+    /// Cells from `start` towards the bottom right corner.
+    pub fn diagonal(&self, start: Pos) -> impl Iterator<Item = &T> {
+        self.ray(start, (1, 1)).map(|p| &self[p])
+    }
+
+    /// Cells from `start` towards the bottom left corner.
+    pub fn anti_diagonal(&self, start: Pos) -> impl Iterator<Item = &T> {
+        self.ray(start, (1, -1)).map(|p| &self[p])
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid { cells: self.cells.iter().map(f).collect(), rows: self.rows, columns: self.columns }
+    }
+
+    fn rebuild(&self, rows: usize, columns: usize, source: impl Fn(Pos) -> Pos) -> Self where T: Clone {
+        let cells = (0..rows * columns)
+            .map(|i| self[source((i / columns, i % columns))].clone())
+            .collect();
+
+        Grid { cells, rows, columns }
+    }
+
+    /// Mirrors the grid along its main diagonal, rows becoming columns.
+    pub fn transpose(&self) -> Self where T: Clone {
+        self.rebuild(self.columns, self.rows, |(r, c)| (c, r))
+    }
+
+    /// Rotates the grid a quarter turn clockwise.
+    pub fn rotate_right(&self) -> Self where T: Clone {
+        self.rebuild(self.columns, self.rows, |(r, c)| (self.rows - 1 - c, r))
+    }
+
+    /// Rotates the grid a quarter turn counterclockwise.
+    pub fn rotate_left(&self) -> Self where T: Clone {
+        self.rebuild(self.columns, self.rows, |(r, c)| (c, self.columns - 1 - r))
+    }
+}
+
+impl Grid<u8> {
+    /// Parses a map of single decimal digits, like height or energy levels.
+    pub fn parse_digits(data: &str) -> Result<Self> {
+        Self::parse(data, |c| c.to_digit(10).map(|d| d as u8).ok_or_else(|| anyhow!("'{}' is not a digit", c)))
+    }
+}
+
+impl Grid<char> {
+    pub fn parse_chars(data: &str) -> Result<Self> {
+        Self::parse(data, Ok)
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        assert!(self.contains(pos), "{:?} is outside of the {}x{} grid", pos, self.rows, self.columns);
+        &self.cells[Grid::index(self, pos)]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        assert!(self.contains(pos), "{:?} is outside of the {}x{} grid", pos, self.rows, self.columns);
+        let index = Grid::index(self, pos);
+        &mut self.cells[index]
+    }
+}
+
+/// One line per row, cells written next to each other.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.rows {
+            for cell in self.row(row) {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_grid_parsing() {
+        let grid = Grid::parse_digits("123\n456\n\n").unwrap();
+
+        assert_eq!((grid.rows(), grid.columns(), grid.len()), (2, 3, 6));
+        assert_eq!(grid[(1, 2)], 6);
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.pos(4), (1, 1));
+        assert_eq!(grid.index((1, 1)), 4);
+        assert_eq!(grid.to_string(), "123\n456\n");
+
+        assert!(Grid::parse_digits("12\n345").is_err());
+        assert!(Grid::parse_digits("12\n3x").is_err());
+        assert_eq!(Grid::parse_chars("#.\n.#").unwrap().cells(), &['#', '.', '.', '#']);
+        assert_eq!(Grid::parse_chars("\n# \n .\n\n").unwrap().cells(), &['#', ' ', ' ', '.']);
+        assert!(Grid::parse_chars("  #\n#").is_err());
+        assert!(Grid::new(2, 2, vec![1, 2, 3]).is_err());
+    }
+
+    #[test]
+    pub fn test_grid_updates() {
+        let mut grid = Grid::filled(2, 2, 0);
+
+        *grid.get_mut((1, 0)).unwrap() = 3;
+        grid.cells_mut()[1] = 2;
+        grid[(1, 1)] = 4;
+        assert_eq!(grid.to_string(), "02\n34\n");
+        assert_eq!(grid.get_mut((2, 0)), None);
+        assert!(!grid.is_empty() && Grid::<u8>::filled(0, 3, 0).is_empty());
+        assert_eq!(manhattan((1, 4), (3, 1)), 5);
+    }
+
+    #[test]
+    pub fn test_grid_neighbors() {
+        let grid = Grid::parse_digits("123\n456\n789").unwrap();
+        let values = |positions: Vec<Pos>| positions.into_iter().map(|p| grid[p]).collect::<Vec<_>>();
+
+        assert_eq!(values(grid.neighbors4((0, 0)).collect()), vec![2, 4]);
+        assert_eq!(values(grid.neighbors4((1, 1)).collect()), vec![2, 4, 6, 8]);
+        assert_eq!(values(grid.neighbors4((2, 1)).collect()), vec![5, 7, 9]);
+        assert_eq!(values(grid.neighbors8((0, 2)).collect()), vec![2, 5, 6]);
+        assert_eq!(values(grid.neighbors8((1, 1)).collect()), vec![1, 2, 3, 4, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    pub fn test_grid_lines_and_rotation() {
+        let grid = Grid::parse_digits("123\n456").unwrap();
+
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), vec![3, 6]);
+        assert_eq!(grid.diagonal((0, 0)).copied().collect::<Vec<_>>(), vec![1, 5]);
+        assert_eq!(grid.anti_diagonal((0, 2)).copied().collect::<Vec<_>>(), vec![3, 5]);
+        assert_eq!(grid.ray((1, 2), (0, -1)).collect::<Vec<_>>(), vec![(1, 2), (1, 1), (1, 0)]);
+        assert_eq!(grid.iter().nth(4), Some(((1, 1), &5)));
+
+        assert_eq!(grid.transpose().to_string(), "14\n25\n36\n");
+        assert_eq!(grid.rotate_right().to_string(), "41\n52\n63\n");
+        assert_eq!(grid.rotate_left().to_string(), "36\n25\n14\n");
+        assert_eq!(grid.rotate_right().rotate_left(), grid);
+        assert_eq!(grid.map(|&d| d * 2).row(0), &[2, 4, 6]);
+    }
+}
@@ -0,0 +1,198 @@
+use anyhow::{ Result, Context };
+use log::{ info, debug };
+use std::io::Read;
+use std::path::PathBuf;
+
+use crate::common::AocDate;
+use crate::config::Settings;
+
+/// Somewhere puzzle inputs can be read from.
+pub trait InputSource {
+    /// Returns the input of a day, or `None` if this source does not have it.
+    fn load(&self, date: &AocDate) -> Result<Option<String>>;
+
+    /// Keeps an input obtained elsewhere; sources that can't store anything ignore it.
+    fn store(&self, _date: &AocDate, _input: &str) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Inputs cached on disk as `<dir>/<year>/day<N>.txt`.
+pub struct FileCache {
+    dir: PathBuf,
+    extension: &'static str,
+}
+
+impl FileCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self::with_extension(dir, "txt")
+    }
+
+    /// Cache of other per-day files, such as puzzle descriptions.
+    pub fn with_extension(dir: PathBuf, extension: &'static str) -> Self {
+        FileCache { dir, extension }
+    }
+
+    fn file_path(&self, date: &AocDate) -> PathBuf {
+        let mut path_buf = self.dir.clone();
+        path_buf.push(date.year.to_string());
+        path_buf.push(format!("day{}.{}", date.day, self.extension));
+
+        debug!("File for {}: {}", date, path_buf.to_string_lossy());
+
+        path_buf
+    }
+}
+
+impl InputSource for FileCache {
+    fn load(&self, date: &AocDate) -> Result<Option<String>> {
+        let input_path = self.file_path(date);
+
+        if !input_path.exists() {
+            return Ok(None);
+        }
+
+        info!("Reading {}", input_path.to_string_lossy());
+
+        std::fs::read_to_string(&input_path)
+            .with_context(|| format!("Unable to read {}", input_path.to_string_lossy()))
+            .map(Some)
+    }
+
+    fn store(&self, date: &AocDate, input: &str) -> Result<()> {
+        let input_path = self.file_path(date);
+        std::fs::create_dir_all(input_path.parent().unwrap())?;
+
+        std::fs::write(&input_path, input)
+            .with_context(|| format!("Unable to store downloaded data in {}", input_path.to_string_lossy()))
+    }
+}
+
+/// Inputs downloaded from the AoC site with the configured session token.
+pub struct Remote<'a> {
+    settings: &'a Settings,
+}
+
+impl<'a> Remote<'a> {
+    pub fn new(settings: &'a Settings) -> Self {
+        Remote { settings }
+    }
+}
+
+impl InputSource for Remote<'_> {
+    fn load(&self, date: &AocDate) -> Result<Option<String>> {
+        date.ensure_unlocked()?;
+        let client = self.settings.client()?;
+
+        info!("Downloading input for {}", date);
+        client.fetch_input(date).map(Some)
+    }
+}
+
+/// Whatever is piped into the process, regardless of the date.
+pub struct Stdin;
+
+impl InputSource for Stdin {
+    fn load(&self, _date: &AocDate) -> Result<Option<String>> {
+        let mut data = String::new();
+        std::io::stdin().read_to_string(&mut data).context("Unable to read input from stdin")?;
+
+        Ok(Some(data))
+    }
+}
+
+/// An explicitly given file, regardless of the date.
+pub struct FilePath(pub PathBuf);
+
+impl InputSource for FilePath {
+    fn load(&self, _date: &AocDate) -> Result<Option<String>> {
+        std::fs::read_to_string(&self.0)
+            .with_context(|| format!("Unable to read input from {}", self.0.to_string_lossy()))
+            .map(Some)
+    }
+}
+
+/// A fixed string, regardless of the date, for callers that already hold the input.
+#[allow(dead_code)]
+pub struct Memory(pub String);
+
+impl InputSource for Memory {
+    fn load(&self, _date: &AocDate) -> Result<Option<String>> {
+        Ok(Some(self.0.clone()))
+    }
+}
+
+/// Tries sources in order. An input found by a later source is stored into
+/// the ones tried before it, so a file cache in front of the site fills itself.
+#[derive(Default)]
+pub struct Chain<'a> {
+    sources: Vec<Box<dyn InputSource + 'a>>,
+}
+
+impl<'a> Chain<'a> {
+    pub fn new() -> Self {
+        Chain { sources: Vec::new() }
+    }
+
+    pub fn then(mut self, source: impl InputSource + 'a) -> Self {
+        self.sources.push(Box::new(source));
+        self
+    }
+}
+
+impl InputSource for Chain<'_> {
+    fn load(&self, date: &AocDate) -> Result<Option<String>> {
+        for (i, source) in self.sources.iter().enumerate() {
+            if let Some(input) = source.load(date)? {
+                for missed in &self.sources[..i] {
+                    missed.store(date, &input)?;
+                }
+
+                return Ok(Some(input));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn store(&self, date: &AocDate, input: &str) -> Result<()> {
+        self.sources.iter().try_for_each(|s| s.store(date, input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_chain_fills_file_cache() {
+        let dir = std::env::temp_dir().join(format!("aoc-rs-input-{}", std::process::id()));
+        let date = AocDate { year: 2021, day: 6 };
+
+        assert_eq!(FileCache::new(dir.clone()).load(&date).unwrap(), None);
+        assert_eq!(Chain::new().then(FileCache::new(dir.clone())).load(&date).unwrap(), None);
+
+        let chain = Chain::new()
+            .then(FileCache::new(dir.clone()))
+            .then(Memory("3,4,3,1,2".to_string()));
+
+        assert_eq!(chain.load(&date).unwrap().as_deref(), Some("3,4,3,1,2"));
+        assert_eq!(std::fs::read_to_string(dir.join("2021").join("day6.txt")).unwrap(), "3,4,3,1,2");
+
+        let cached = Chain::new()
+            .then(FileCache::new(dir.clone()))
+            .then(Memory("ignored".to_string()));
+        assert_eq!(cached.load(&date).unwrap().as_deref(), Some("3,4,3,1,2"));
+        assert_eq!(date.load_data(&cached).unwrap(), "3,4,3,1,2");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    pub fn test_file_path_source() {
+        let date = AocDate { year: 2021, day: 1 };
+
+        assert!(FilePath(PathBuf::from("/nonexistent/aoc-rs/input.txt")).load(&date).is_err());
+        assert!(date.load_data(&Chain::new()).is_err());
+    }
+}
@@ -1,32 +1,527 @@
+#![allow(clippy::items_after_test_module)]
+
 extern crate lazy_static;
 extern crate log;
 
+mod answers;
+mod bench;
+mod cli;
+mod client;
 mod common;
+mod config;
+mod examples;
+mod grid;
+mod input;
 mod logging;
+mod puzzle;
+mod report;
+mod search;
+mod scaffold;
 mod solutions;
+#[cfg(test)]
+mod testing;
 
-use log::{ LevelFilter, info };
-use common::AocDate;
+use anyhow::{ Result, Context, anyhow };
+use clap::Parser;
+use log::{ info, warn, error };
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::{ Duration, Instant };
 
-fn main() {
-    logging::setup(LevelFilter::Info);
+use answers::{ AnswerStore, Verdict };
+use cli::{ BenchArgs, Cli, Command, DateArgs, DayRange, PuzzleArgs, RecordArgs, RunArgs, SelectArgs, SolveArgs, TestArgs };
+use chrono::Utc;
+use client::{ FetchError, Outcome };
+use common::{ AocDate, Part, Parts, RunReport };
+use config::{ Overrides, Settings };
+use input::{ Chain, FileCache, FilePath, InputSource, Remote, Stdin };
+use report::Format;
+use solutions::ThreadSafeSolvable;
 
-    let args: Vec<String> = std::env::args().collect();
+/// How many times a freshly unlocked input is requested again while the site still reports it locked.
+const UNLOCK_RETRIES: u32 = 5;
 
-    let date = if args.len() > 2 {
-        let year = args[1].parse::<u32>().unwrap();
-        let day = args[2].parse::<u32>().unwrap();
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    logging::setup(cli.log_level);
 
-        AocDate { year, day } 
-    } else if args.len() > 1 {
-        let day = args[1].parse::<i32>().unwrap();
-        AocDate::day(day)
-    } else {
-        AocDate::today()
+    let overrides = Overrides {
+        token: cli.token,
+        config: cli.config,
+        input_dir: cli.input_dir,
+        data_dir: cli.data_dir,
+        account: cli.account,
     };
 
+    let result = Settings::load(overrides).and_then(|settings| match cli.command {
+        Some(command) => run(command, &settings, cli.format),
+        None => run_batch(&cli.run, &settings, cli.format),
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            error!("{:#}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: Command, settings: &Settings, format: Option<Format>) -> Result<()> {
+    match command {
+        Command::Solve { args, wait } => {
+            if wait {
+                // Fail right away rather than after sleeping until unlock.
+                let date = args.date.date()?;
+                solutions::find(&date)?;
+                wait_for_unlock(&date);
+            }
+            solve(&args, settings, format)
+        },
+        Command::Fetch { date, wait } => {
+            let date = date.date()?;
+            if wait {
+                wait_for_unlock(&date);
+            }
+            fetch(&date, settings)
+        },
+        Command::New { date, fetch } => new_day(&date.date()?, fetch, settings),
+        Command::Puzzle(args) => show_puzzle(&args, settings),
+        Command::List { year } => list(year),
+        Command::Test(args) => test(&args, settings),
+        Command::Bench(args) => bench(&args, settings),
+        Command::Verify(args) => verify(&args, settings),
+        Command::Examples(args) => check_examples(&args),
+        Command::Record(args) => record(&args, settings),
+        Command::Submit(args) => submit(&args, settings),
+    }
+}
+
+fn input_source<'a>(input: Option<&Path>, settings: &'a Settings) -> Box<dyn InputSource + 'a> {
+    match input {
+        Some(path) if path.as_os_str() == "-" => Box::new(Stdin),
+        Some(path) => Box::new(FilePath(path.to_path_buf())),
+        None => Box::new(
+            Chain::new()
+                .then(FileCache::new(settings.account_dir()))
+                .then(Remote::new(settings))
+        ),
+    }
+}
+
+fn read_input(date: &AocDate, input: Option<&Path>, settings: &Settings) -> Result<String> {
+    date.load_data(input_source(input, settings).as_ref()).context("Unable to load input data")
+}
+
+fn solve(args: &SolveArgs, settings: &Settings, format: Option<Format>) -> Result<()> {
+    let date = args.date.date()?;
+    let solution = solutions::find(&date)?;
+
     info!("Solving {}", date);
- 
-    solutions::find(&date).unwrap().solve();
+
+    let start = Instant::now();
+    let input = match args.input.as_deref() {
+        Some(path) => read_input(&date, Some(path), settings)?,
+        None => read_unlocked_input(&date, settings)?,
+    };
+    let load_elapsed = start.elapsed();
+    let report = RunReport { load_elapsed: Some(load_elapsed), ..solution.solve(&input, args.parts()) };
+
+    if let Some(elapsed) = report.parse_elapsed {
+        info!("Parsed input for {} in {}", date, report::format_duration(elapsed));
+    }
+
+    if let Some(error) = &report.error {
+        error!("Error solving {}: {}", date, error);
+    }
+
+    if let Some(format) = format {
+        print!("{}", report::render(std::slice::from_ref(&report), format));
+    }
+
+    if report.combined {
+        info!("Solved both parts of {} in one pass ({})", date, report::format_duration(report.parts[0].elapsed));
+    }
+
+    for p in &report.parts {
+        match &p.answer {
+            Ok(answer) if report.combined => info!("{} part {}: {}", date, p.part, answer),
+            Ok(answer) => info!("{} part {}: {} ({})", date, p.part, answer, report::format_duration(p.elapsed)),
+            Err(error) => error!("Error solving {} part {}: {}", date, p.part, error),
+        }
+    }
+
+    if report.is_ok() {
+        Ok(())
+    } else {
+        Err(anyhow!("Failed to solve {}", date))
+    }
+}
+
+fn run_batch(args: &RunArgs, settings: &Settings, format: Option<Format>) -> Result<()> {
+    let year = match args.year {
+        Some(year) => year,
+        None => {
+            return solve(&SolveArgs {
+                date: DateArgs { year: None, day: None },
+                part: None,
+                input: None,
+            }, settings, format)
+        }
+    };
+
+    let reports = run_all(&select(Some(year), args.days)?, settings);
+
+    print!("{}", report::render(&reports, format.unwrap_or(Format::Table)));
+
+    let failed = reports.iter().filter(|r| !r.is_ok()).count();
+    if failed > 0 {
+        Err(anyhow!("{} of {} day(s) failed", failed, reports.len()))
+    } else {
+        Ok(())
+    }
+}
+
+fn select(year: Option<u32>, days: Option<DayRange>) -> Result<Vec<&'static ThreadSafeSolvable>> {
+    let selected: Vec<&ThreadSafeSolvable> = solutions::all().into_iter()
+        .filter(|s| year.is_none_or(|y| y == s.get_date().year))
+        .filter(|s| days.is_none_or(|r| r.contains(s.get_date().day)))
+        .collect();
+
+    if selected.is_empty() {
+        Err(anyhow!("No solutions registered in the requested range"))
+    } else {
+        Ok(selected)
+    }
+}
+
+fn run_all(selected: &[&ThreadSafeSolvable], settings: &Settings) -> Vec<RunReport> {
+    selected.iter()
+        .map(|solution| {
+            let date = solution.get_date();
+            info!("Solving {}", date);
+
+            let start = Instant::now();
+
+            match read_input(&date, None, settings) {
+                Ok(input) => RunReport { load_elapsed: Some(start.elapsed()), ..solution.solve(&input, Parts::Both) },
+                Err(e) => RunReport::failed(date, &e),
+            }
+        })
+        .collect()
 }
 
+/// Sleeps until the puzzle unlocks, reporting the remaining time now and then.
+fn wait_for_unlock(date: &AocDate) {
+    while let Some(wait) = date.time_to_unlock(Utc::now()) {
+        info!("Waiting for {} to unlock, {} left", date, common::format_wait(wait));
+
+        let step = if wait > Duration::from_secs(3600) {
+            Duration::from_secs(3600)
+        } else if wait > Duration::from_secs(60) {
+            Duration::from_secs(60)
+        } else {
+            wait
+        };
+
+        std::thread::sleep(step);
+    }
+}
+
+/// Reads the input, giving the site a few more seconds if it still reports
+/// the puzzle as locked right after the unlock time.
+fn read_unlocked_input(date: &AocDate, settings: &Settings) -> Result<String> {
+    let mut attempts = 0;
+
+    loop {
+        match read_input(date, None, settings) {
+            Err(e) if attempts < UNLOCK_RETRIES && e.downcast_ref::<FetchError>() == Some(&FetchError::NotUnlocked) => {
+                attempts += 1;
+                warn!("{} is not available yet, retrying ({}/{})", date, attempts, UNLOCK_RETRIES);
+                std::thread::sleep(Duration::from_secs(2));
+            },
+            result => return result,
+        }
+    }
+}
+
+fn fetch(date: &AocDate, settings: &Settings) -> Result<()> {
+    read_unlocked_input(date, settings)?;
+    info!("Input for {} is available", date);
+
+    Ok(())
+}
+
+fn new_day(date: &AocDate, fetch: bool, settings: &Settings) -> Result<()> {
+    let examples_dir = examples::default_dir();
+
+    for path in scaffold::create(&scaffold::solutions_dir(), &examples_dir, date)? {
+        info!("Wrote {}", path.to_string_lossy());
+    }
+
+    if fetch {
+        date.ensure_unlocked()?;
+        read_input(date, None, settings)?;
+        info!("Input for {} is available", date);
+
+        let html = puzzle::load(date, settings, false)?;
+
+        if puzzle::example_blocks(&html).is_empty() {
+            warn!("The description of {} has no example block", date);
+        } else {
+            extract_example(date, &html, 1, "example", false)?;
+        }
+    }
+
+    info!("Rebuild to include {}", date);
+
+    Ok(())
+}
+
+fn show_puzzle(args: &PuzzleArgs, settings: &Settings) -> Result<()> {
+    let date = args.date.date()?;
+    let mut html = puzzle::load(&date, settings, args.refresh)?;
+
+    if !args.refresh && puzzle::articles(&html).len() < 2 && AnswerStore::new(settings.answers_dir()).load(&date)?.part1.is_some() {
+        info!("Part 1 of {} is solved, downloading the description again for part 2", date);
+        html = puzzle::load(&date, settings, true)?;
+    }
+
+    if args.extract {
+        extract_example(&date, &html, args.block as usize, &args.name, args.force)
+    } else {
+        print!("{}", puzzle::render(&html, args.markdown));
+        Ok(())
+    }
+}
+
+/// Writes a code block of a description as an example input of the day.
+fn extract_example(date: &AocDate, html: &str, block: usize, name: &str, force: bool) -> Result<()> {
+    let blocks = puzzle::example_blocks(html);
+    let example = blocks.get(block - 1)
+        .ok_or_else(|| anyhow!("The description of {} has {} code block(s), not {}", date, blocks.len(), block))?;
+
+    let dir = examples::day_dir(&examples::default_dir(), date);
+    let path = dir.join(format!("{}.txt", name));
+
+    if !force && std::fs::read_to_string(&path).is_ok_and(|content| !content.is_empty()) {
+        return Err(anyhow!("{} already exists, use --force to overwrite it", path.to_string_lossy()));
+    }
+
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(&path, example)?;
+    info!("Wrote code block {} of {} to {}", block, blocks.len(), path.to_string_lossy());
+
+    Ok(())
+}
+
+fn list(year: Option<u32>) -> Result<()> {
+    let mark = |implemented: bool, part: Part| if implemented { part.to_string() } else { "-".to_string() };
+
+    for (date, part1, part2) in solutions::list() {
+        if year.is_none_or(|y| y == date.year) {
+            println!("{:<8} {} {}", date.to_string(), mark(part1, Part::One), mark(part2, Part::Two));
+        }
+    }
+
+    Ok(())
+}
+
+fn test(args: &TestArgs, settings: &Settings) -> Result<()> {
+    let date = args.solve.date.date()?;
+    let solution = solutions::find(&date)?;
+    let input = read_input(&date, args.solve.input.as_deref(), settings)?;
+    let parts = args.solve.parts().to_vec();
+    let mut failures = 0;
+
+    for part in parts {
+        match (solution.run(part, &input), args.expected(part)) {
+            (Ok(answer), Some(expected)) if answer == expected =>
+                info!("{} part {}: {} (ok)", date, part, answer),
+            (Ok(answer), Some(expected)) => {
+                error!("{} part {}: {} (expected {})", date, part, answer, expected);
+                failures += 1;
+            },
+            (Ok(answer), None) => info!("{} part {}: {}", date, part, answer),
+            (Err(e), _) => {
+                error!("Error solving {} part {}: {}", date, part, e);
+                failures += 1;
+            },
+        }
+    }
+
+    if failures > 0 {
+        Err(anyhow!("{} check(s) failed for {}", failures, date))
+    } else {
+        Ok(())
+    }
+}
+
+fn bench(args: &BenchArgs, settings: &Settings) -> Result<()> {
+    let date = args.solve.date.date()?;
+    let solution = solutions::find(&date)?;
+
+    let start = Instant::now();
+    let input = read_input(&date, args.solve.input.as_deref(), settings)?;
+    info!("Loaded input for {} in {}", date, report::format_duration(start.elapsed()));
+
+    let start = Instant::now();
+    let solver = solution.prepare(&input).with_context(|| format!("Unable to parse input of {}", date))?;
+    info!("Parsed input for {} in {}", date, report::format_duration(start.elapsed()));
+
+    for part in args.solve.parts().to_vec() {
+        let (answer, stats) = bench::run(&solver, part, args.warmup as usize, args.iterations as usize)
+            .with_context(|| format!("Benchmark of {} part {} failed", date, part))?;
+
+        info!(
+            "{} part {}: {} | runs: {}, min: {}, median: {}, mean: {}, stddev: {}",
+            date, part, answer, stats.runs,
+            report::format_duration(stats.min),
+            report::format_duration(stats.median),
+            report::format_duration(stats.mean),
+            report::format_duration(stats.stddev),
+        );
+    }
+
+    Ok(())
+}
+
+fn verify(args: &SelectArgs, settings: &Settings) -> Result<()> {
+    let store = AnswerStore::new(settings.answers_dir());
+    let (mut passed, mut failed, mut unrecorded) = (0, 0, 0);
+
+    for report in run_all(&select(args.year, args.days)?, settings) {
+        let answers = store.load(&report.date)?;
+
+        for (part, verdict) in answers::check(&report, &answers) {
+            println!("{} part {}: {}", report.date, part, verdict);
+
+            match verdict {
+                Verdict::Pass => passed += 1,
+                Verdict::Unrecorded => unrecorded += 1,
+                _ => failed += 1,
+            }
+        }
+    }
+
+    println!("{} passed, {} failed, {} without recorded answer", passed, failed, unrecorded);
+
+    if failed > 0 {
+        Err(anyhow!("Verification failed for {} part(s)", failed))
+    } else {
+        Ok(())
+    }
+}
+
+fn check_examples(args: &SelectArgs) -> Result<()> {
+    let root = examples::default_dir();
+    let (mut passed, mut failed) = (0, 0);
+
+    for solution in select(args.year, args.days)? {
+        let date = solution.get_date();
+
+        for example in examples::load(&root, &date)? {
+            for (part, verdict) in examples::check(solution, &example) {
+                println!("{} {} part {}: {}", date, example.name, part, verdict);
+
+                match verdict {
+                    Verdict::Pass => passed += 1,
+                    _ => failed += 1,
+                }
+            }
+        }
+    }
+
+    println!("{} passed, {} failed", passed, failed);
+
+    if failed > 0 {
+        Err(anyhow!("{} example check(s) failed", failed))
+    } else {
+        Ok(())
+    }
+}
+
+fn record(args: &RecordArgs, settings: &Settings) -> Result<()> {
+    let date = args.solve.date.date()?;
+    let store = AnswerStore::new(settings.answers_dir());
+
+    if let (Some(answer), Some(part)) = (&args.answer, args.part()?) {
+        store.record(&date, part, answer)?;
+        info!("Recorded {} part {}: {}", date, part, answer);
+
+        return Ok(());
+    }
+
+    let solution = solutions::find(&date)?;
+    let input = read_input(&date, args.solve.input.as_deref(), settings)?;
+    let report = solution.solve(&input, args.solve.parts());
+
+    if let Some(error) = &report.error {
+        error!("Error solving {}: {}", date, error);
+    }
+
+    for p in &report.parts {
+        match &p.answer {
+            Ok(answer) => {
+                store.record(&date, p.part, answer)?;
+                info!("Recorded {} part {}: {}", date, p.part, answer);
+            },
+            Err(error) => error!("Error solving {} part {}: {}", date, p.part, error),
+        }
+    }
+
+    if report.is_ok() {
+        Ok(())
+    } else {
+        Err(anyhow!("Failed to solve {}", date))
+    }
+}
+
+fn submit(args: &RecordArgs, settings: &Settings) -> Result<()> {
+    let date = args.solve.date.date()?;
+    let store = AnswerStore::new(settings.answers_dir());
+    let answers = store.load(&date)?;
+
+    let part = match args.solve.part {
+        Some(parts) => parts.single().ok_or_else(|| anyhow!("Answers are submitted one part at a time"))?,
+        None if answers.part1.is_none() => Part::One,
+        None => Part::Two,
+    };
+
+    let answer = match &args.answer {
+        Some(answer) => answer.clone(),
+        None => {
+            let solution = solutions::find(&date)?;
+            let input = read_input(&date, args.solve.input.as_deref(), settings)?;
+
+            solution.run(part, &input)
+                .with_context(|| format!("Unable to solve {} part {}", date, part))?
+        },
+    };
+
+    if let Some(reason) = answers.rejection(part, &answer) {
+        return Err(anyhow!("Not submitting {} for {} part {}: {}", answer, date, part, reason));
+    }
+
+    date.ensure_unlocked()?;
+    info!("Submitting {} for {} part {}", answer, date, part);
+    let outcome = settings.client()?.submit(&date, part, &answer)?;
+
+    match outcome {
+        Outcome::Correct => {
+            store.record_submission(&date, part, &answer, &outcome)?;
+            info!("{} part {}: {} is correct", date, part, answer);
+            Ok(())
+        },
+        Outcome::AlreadySolved => {
+            warn!("{} part {} is already solved or not unlocked yet", date, part);
+            Ok(())
+        },
+        Outcome::RateLimited(wait) =>
+            Err(anyhow!("Submitted too recently, retry in {}s", wait.as_secs())),
+        _ => {
+            store.record_submission(&date, part, &answer, &outcome)?;
+            Err(anyhow!("{} part {}: {} is {}", date, part, answer, outcome))
+        },
+    }
+}
@@ -0,0 +1,183 @@
+use anyhow::Result;
+use lazy_static::lazy_static;
+use log::info;
+use regex::{ Captures, Regex };
+
+use crate::common::AocDate;
+use crate::config::Settings;
+use crate::input::{ Chain, FileCache, InputSource };
+
+/// Puzzle descriptions downloaded from the AoC site with the configured session token.
+pub struct RemoteDescription<'a> {
+    settings: &'a Settings,
+}
+
+impl<'a> RemoteDescription<'a> {
+    pub fn new(settings: &'a Settings) -> Self {
+        RemoteDescription { settings }
+    }
+}
+
+impl InputSource for RemoteDescription<'_> {
+    fn load(&self, date: &AocDate) -> Result<Option<String>> {
+        date.ensure_unlocked()?;
+        let client = self.settings.client()?;
+
+        info!("Downloading description of {}", date);
+        client.fetch_description(date).map(Some)
+    }
+}
+
+/// Descriptions are cached as `<account dir>/<year>/day<N>.html` next to the inputs,
+/// since part 2 only shows up for an account once it solved part 1.
+pub fn cache(settings: &Settings) -> FileCache {
+    FileCache::with_extension(settings.account_dir(), "html")
+}
+
+/// Loads the description page of a day, from the cache if it was downloaded before.
+/// With `refresh` it is downloaded again and the cached copy replaced.
+pub fn load(date: &AocDate, settings: &Settings, refresh: bool) -> Result<String> {
+    if refresh {
+        let html = date.load_data(&RemoteDescription::new(settings))?;
+        cache(settings).store(date, &html)?;
+
+        return Ok(html);
+    }
+
+    date.load_data(&Chain::new().then(cache(settings)).then(RemoteDescription::new(settings)))
+}
+
+/// Replaces the HTML entities used on puzzle pages with the characters they stand for.
+pub fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Removes inline markup such as `<em>` from a fragment, keeping its text.
+pub fn strip_tags(html: &str) -> String {
+    lazy_static! {
+        static ref TAG: Regex = Regex::new(r"<[^>]*>").unwrap();
+    }
+
+    TAG.replace_all(html, "").to_string()
+}
+
+/// Text of the `<pre><code>` blocks of a puzzle description, in page order.
+/// The example input is usually the first one.
+pub fn example_blocks(html: &str) -> Vec<String> {
+    lazy_static! {
+        static ref BLOCK: Regex = Regex::new(r"(?s)<pre><code>(.*?)</code></pre>").unwrap();
+    }
+
+    BLOCK.captures_iter(html)
+        .map(|caps| unescape(&strip_tags(&caps[1])))
+        .collect()
+}
+
+/// The `<article>` elements of a page, one per part revealed so far.
+pub fn articles(html: &str) -> Vec<&str> {
+    lazy_static! {
+        static ref ARTICLE: Regex = Regex::new(r"(?s)<article[^>]*>(.*?)</article>").unwrap();
+    }
+
+    ARTICLE.captures_iter(html).map(|caps| caps.get(1).unwrap().as_str()).collect()
+}
+
+/// Renders the articles of a description page as plain text, or as markdown
+/// keeping emphasis, inline code and links.
+pub fn render(html: &str, markdown: bool) -> String {
+    lazy_static! {
+        static ref BLOCK: Regex = Regex::new(r"(?s)<pre><code>(.*?)</code></pre>").unwrap();
+        static ref TITLE: Regex = Regex::new(r"(?s)<h2[^>]*>(.*?)</h2>").unwrap();
+        static ref EM_CODE: Regex = Regex::new(r"(?s)<code><em>(.*?)</em></code>").unwrap();
+        static ref EM: Regex = Regex::new(r"(?s)<em[^>]*>(.*?)</em>").unwrap();
+        static ref CODE: Regex = Regex::new(r"(?s)<code>(.*?)</code>").unwrap();
+        static ref LINK: Regex = Regex::new(r#"(?s)<a [^>]*href="([^"]*)"[^>]*>(.*?)</a>"#).unwrap();
+        static ref BREAKS: Regex = Regex::new(r"\n{3,}").unwrap();
+    }
+
+    let inline = |text: &str| {
+        let text = TITLE.replace_all(text, |caps: &Captures| if markdown {
+            format!("## {}\n\n", caps[1].trim_matches(|c| c == '-' || c == ' '))
+        } else {
+            format!("{}\n\n", &caps[1])
+        });
+        let text = text.replace("<li>", "- ").replace("</li>", "\n").replace("</p>", "\n\n").replace("</ul>", "\n");
+        let text = if markdown {
+            let text = EM_CODE.replace_all(&text, "**`$1`**");
+            let text = EM.replace_all(&text, "*$1*");
+            let text = CODE.replace_all(&text, "`$1`");
+            LINK.replace_all(&text, "[$2]($1)").to_string()
+        } else {
+            text
+        };
+
+        unescape(&strip_tags(&text))
+    };
+
+    let mut text = String::new();
+
+    for article in articles(html) {
+        let mut last = 0;
+
+        for caps in BLOCK.captures_iter(article) {
+            let block = caps.get(0).unwrap();
+            let code = unescape(&strip_tags(&caps[1]));
+
+            text.push_str(&inline(&article[last..block.start()]));
+            if markdown {
+                text.push_str(&format!("```\n{}```\n\n", code));
+            } else {
+                code.lines().for_each(|line| text.push_str(&format!("    {}\n", line)));
+                text.push('\n');
+            }
+            last = block.end();
+        }
+
+        text.push_str(&inline(&article[last..]));
+        text.push_str("\n\n");
+    }
+
+    format!("{}\n", BREAKS.replace_all(text.trim(), "\n\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = "<html><body><main>\n<article class=\"day-desc\"><h2>--- Day 9: Smoke Basin ---</h2>\
+        <p>Consider the <a href=\"https://en.wikipedia.org/wiki/Heightmap\">heightmap</a>:</p>\n\
+        <pre><code>21999<em>4</em>3210\n3987894921\n</code></pre>\n\
+        <p>The sum is <code><em>15</em></code>, see <code>a &lt; b</code>.</p>\n\
+        <ul>\n<li>One <em>low</em> point</li>\n</ul>\n</article>\n\
+        <p>Your puzzle answer was <code>15</code>.</p>\n\
+        <article class=\"day-desc\"><h2 id=\"part2\">--- Part Two ---</h2><p>Now find basins.</p></article>\n\
+        </main></body></html>";
+
+    #[test]
+    pub fn test_example_blocks() {
+        let html = "<article><p>For example:</p>\n<pre><code>2199943210\n3987894921\n</code></pre>\n\
+            <p>Then <code>15</code>.</p><pre><code>a -&gt; <em>b</em> &amp; c\n</code></pre></article>";
+
+        assert_eq!(example_blocks(html), vec!["2199943210\n3987894921\n", "a -> b & c\n"]);
+        assert!(example_blocks("<p>No examples</p>").is_empty());
+    }
+
+    #[test]
+    pub fn test_render_description() {
+        assert_eq!(articles(PAGE).len(), 2);
+
+        assert_eq!(render(PAGE, false), "--- Day 9: Smoke Basin ---\n\n\
+            Consider the heightmap:\n\n    2199943210\n    3987894921\n\n\
+            The sum is 15, see a < b.\n\n- One low point\n\n\
+            --- Part Two ---\n\nNow find basins.\n");
+
+        assert_eq!(render(PAGE, true), "## Day 9: Smoke Basin\n\n\
+            Consider the [heightmap](https://en.wikipedia.org/wiki/Heightmap):\n\n```\n2199943210\n3987894921\n```\n\n\
+            The sum is **`15`**, see `a < b`.\n\n- One *low* point\n\n\
+            ## Part Two\n\nNow find basins.\n");
+    }
+}
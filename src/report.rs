@@ -0,0 +1,309 @@
+use anyhow::{ Result, anyhow };
+use serde::Serialize;
+use std::fmt::{ self, Write };
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::common::{ AocDate, Part, RunReport };
+
+const HEADER: [&str; 6] = ["Day", "Parse", "Part 1", "Time", "Part 2", "Time"];
+
+pub fn format_duration(d: Duration) -> String {
+    let micros = d.as_secs_f64() * 1_000_000.0;
+
+    if micros < 1_000.0 {
+        format!("{:.1} µs", micros)
+    } else if micros < 1_000_000.0 {
+        format!("{:.2} ms", micros / 1_000.0)
+    } else {
+        format!("{:.2} s", micros / 1_000_000.0)
+    }
+}
+
+fn row(report: &RunReport) -> [String; 6] {
+    let mut cells: [String; 6] = Default::default();
+    cells[0] = report.date.to_string();
+    cells[1] = report.parse_elapsed.map(format_duration).unwrap_or_default();
+
+    if report.error.is_some() {
+        cells[2] = "ERROR".to_string();
+        return cells;
+    }
+
+    for (i, part) in Part::ALL.iter().enumerate() {
+        let (answer, time) = match report.part(*part) {
+            Some(p) => (
+                p.answer.as_ref().map_or_else(|_| "ERROR".to_string(), |a| a.clone()),
+                format_duration(p.elapsed),
+            ),
+            None => ("-".to_string(), String::new()),
+        };
+
+        cells[2 + 2 * i] = answer;
+        cells[3 + 2 * i] = time;
+    }
+
+    // A single pass solved both parts, its time is only shown once
+    if report.combined && report.part(Part::Two).is_some() {
+        cells[5] = "combined".to_string();
+    }
+
+    cells
+}
+
+/// Renders a run summary as a text table, followed by the errors of failed days.
+pub fn table(reports: &[RunReport]) -> String {
+    let rows: Vec<[String; 6]> = reports.iter().map(row).collect();
+    let mut widths: Vec<usize> = HEADER.iter().map(|h| h.chars().count()).collect();
+
+    for r in &rows {
+        for (w, cell) in widths.iter_mut().zip(r.iter()) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let format_row = |out: &mut String, cells: &[&str]| {
+        let line: Vec<String> = cells.iter().zip(&widths).enumerate()
+            .map(|(i, (c, w))| if i % 2 == 1 { format!(" {:>w$} ", c, w = w) } else { format!(" {:<w$} ", c, w = w) })
+            .collect();
+        let _ = writeln!(out, "{}", line.join("|").trim_end());
+    };
+
+    format_row(&mut out, &HEADER);
+    let _ = writeln!(out, "{}", widths.iter().map(|w| "-".repeat(w + 2)).collect::<Vec<_>>().join("+"));
+
+    for r in &rows {
+        format_row(&mut out, &r.iter().map(|c| c.as_str()).collect::<Vec<_>>());
+    }
+
+    for report in reports {
+        if let Some(error) = &report.error {
+            let _ = writeln!(out, "{}: {}", report.date, error);
+        }
+
+        for p in &report.parts {
+            if let Err(error) = &p.answer {
+                let _ = writeln!(out, "{} part {}: {}", report.date, p.part, error);
+            }
+        }
+    }
+
+    out
+}
+
+/// How run results are printed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Table => write!(f, "table"),
+            Format::Json => write!(f, "json"),
+            Format::Csv => write!(f, "csv"),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(anyhow!("Unknown output format '{}', expected table, json or csv", s)),
+        }
+    }
+}
+
+pub fn render(reports: &[RunReport], format: Format) -> String {
+    match format {
+        Format::Table => table(reports),
+        Format::Json => json(reports),
+        Format::Csv => csv(reports),
+    }
+}
+
+fn nanos(d: Duration) -> u64 {
+    d.as_nanos() as u64
+}
+
+#[derive(Serialize)]
+struct JsonPart<'a> {
+    part: Part,
+    answer: Option<&'a str>,
+    error: Option<&'a str>,
+    elapsed_ns: u64,
+}
+
+#[derive(Serialize)]
+struct JsonRun<'a> {
+    year: u32,
+    day: u32,
+    input_hash: Option<&'a str>,
+    load_ns: Option<u64>,
+    parse_ns: Option<u64>,
+    combined: bool,
+    error: Option<&'a str>,
+    parts: Vec<JsonPart<'a>>,
+}
+
+impl<'a> From<&'a RunReport> for JsonRun<'a> {
+    fn from(report: &'a RunReport) -> Self {
+        let AocDate { year, day } = report.date;
+
+        JsonRun {
+            year,
+            day,
+            input_hash: report.input_hash.as_deref(),
+            load_ns: report.load_elapsed.map(nanos),
+            parse_ns: report.parse_elapsed.map(nanos),
+            combined: report.combined,
+            error: report.error.as_deref(),
+            parts: report.parts.iter()
+                .map(|p| JsonPart {
+                    part: p.part,
+                    answer: p.answer.as_deref().ok(),
+                    error: p.answer.as_ref().err().map(String::as_str),
+                    elapsed_ns: nanos(p.elapsed),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Renders the runs as a JSON array, durations in nanoseconds.
+pub fn json(reports: &[RunReport]) -> String {
+    let runs: Vec<JsonRun> = reports.iter().map(JsonRun::from).collect();
+
+    serde_json::to_string_pretty(&runs).expect("Run reports are always serializable") + "\n"
+}
+
+const CSV_HEADER: &str = "year,day,part,answer,error,elapsed_ns,parse_ns,load_ns,input_hash";
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Renders the runs as CSV with one row per part; a run that failed as a whole
+/// gets a single row without a part.
+pub fn csv(reports: &[RunReport]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "{}", CSV_HEADER);
+
+    for report in reports {
+        let optional = |d: Option<Duration>| d.map(|d| nanos(d).to_string()).unwrap_or_default();
+        let common = format!(
+            "{},{},{}",
+            optional(report.parse_elapsed), optional(report.load_elapsed), report.input_hash.as_deref().unwrap_or_default()
+        );
+
+        if let Some(error) = &report.error {
+            let _ = writeln!(out, "{},{},,,{},,{}", report.date.year, report.date.day, csv_field(error), common);
+        }
+
+        for p in &report.parts {
+            let (answer, error) = match &p.answer {
+                Ok(answer) => (csv_field(answer), String::new()),
+                Err(error) => (String::new(), csv_field(error)),
+            };
+
+            let _ = writeln!(out, "{},{},{},{},{},{},{}", report.date.year, report.date.day, p.part, answer, error, nanos(p.elapsed), common);
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::PartReport;
+
+    fn sample_reports() -> Vec<RunReport> {
+        vec![
+            RunReport {
+                parse_elapsed: Some(Duration::from_micros(250)),
+                parts: vec![
+                    PartReport { part: Part::One, answer: Ok("7".to_string()), elapsed: Duration::from_micros(12) },
+                    PartReport { part: Part::Two, answer: Err("Not implemented".to_string()), elapsed: Duration::from_millis(3) },
+                ],
+                ..RunReport::new(AocDate { year: 2021, day: 1 })
+            },
+            RunReport {
+                error: Some("AoC token not set".to_string()),
+                ..RunReport::new(AocDate { year: 2021, day: 2 })
+            },
+            RunReport {
+                parse_elapsed: Some(Duration::from_micros(3)),
+                combined: true,
+                parts: vec![
+                    PartReport { part: Part::One, answer: Ok("5934".to_string()), elapsed: Duration::from_micros(20) },
+                    PartReport { part: Part::Two, answer: Ok("26984457539".to_string()), elapsed: Duration::from_micros(20) },
+                ],
+                ..RunReport::new(AocDate { year: 2021, day: 6 })
+            },
+        ]
+    }
+
+    #[test]
+    pub fn test_table_layout() {
+        let table = table(&sample_reports());
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(lines[0], " Day    |    Parse | Part 1 |    Time | Part 2      |     Time");
+        assert_eq!(lines[2], " 2021/1 | 250.0 µs | 7      | 12.0 µs | ERROR       |  3.00 ms");
+        assert_eq!(lines[3], " 2021/2 |          | ERROR  |         |             |");
+        assert_eq!(lines[4], " 2021/6 |   3.0 µs | 5934   | 20.0 µs | 26984457539 | combined");
+        assert_eq!(lines[5], "2021/1 part 2: Not implemented");
+        assert_eq!(lines[6], "2021/2: AoC token not set");
+    }
+
+    #[test]
+    pub fn test_json_output() {
+        let mut reports = sample_reports();
+        reports[0].input_hash = Some("cbf29ce484222325".to_string());
+        reports[0].load_elapsed = Some(Duration::from_millis(1));
+
+        let runs: serde_json::Value = serde_json::from_str(&json(&reports)).unwrap();
+
+        assert_eq!(runs[0]["year"], 2021);
+        assert_eq!(runs[0]["input_hash"], "cbf29ce484222325");
+        assert_eq!(runs[0]["load_ns"], 1_000_000);
+        assert_eq!(runs[0]["parse_ns"], 250_000);
+        assert_eq!(runs[0]["parts"][0]["part"], 1);
+        assert_eq!(runs[0]["parts"][0]["answer"], "7");
+        assert_eq!(runs[0]["parts"][1]["error"], "Not implemented");
+        assert_eq!(runs[1]["error"], "AoC token not set");
+        assert_eq!(runs[1]["parse_ns"], serde_json::Value::Null);
+        assert_eq!(runs[2]["combined"], true);
+    }
+
+    #[test]
+    pub fn test_csv_output() {
+        let mut reports = sample_reports();
+        reports[1].error = Some("Bad \"input\", really".to_string());
+
+        let csv = csv(&reports);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines[1], "2021,1,1,7,,12000,250000,,");
+        assert_eq!(lines[2], "2021,1,2,,Not implemented,3000000,250000,,");
+        assert_eq!(lines[3], "2021,2,,,\"Bad \"\"input\"\", really\",,,,");
+        assert_eq!(lines.len(), 6);
+        assert_eq!("json".parse::<Format>().unwrap(), Format::Json);
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
@@ -0,0 +1,220 @@
+use anyhow::{ Result, Context, anyhow };
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::{ Path, PathBuf };
+
+use crate::common::AocDate;
+use crate::examples;
+
+const TEMPLATE: &str = include_str!("solutions/day.rs.template");
+
+const EXAMPLE_ANSWERS: &str = "# Expected answers of the examples, one table per example file:\n#\n# [example]\n# part1 = \"\"\n# part2 = \"\"\n";
+
+/// `src/solutions` of the source tree the binary was built from.
+pub fn solutions_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src").join("solutions")
+}
+
+/// Name of the module holding the solutions of a year, like `aoc_21`.
+pub fn year_module(year: u32) -> String {
+    format!("aoc_{:02}", year % 100)
+}
+
+/// Source of a new day module generated from the template.
+pub fn render(date: &AocDate) -> String {
+    TEMPLATE
+        .replace("{{YEAR}}", &date.year.to_string())
+        .replace("{{YY}}", &format!("{:02}", date.year % 100))
+        .replace("{{DAY}}", &date.day.to_string())
+}
+
+/// Adds `item` to the comma separated `list` group of a registration macro call, keeping the list
+/// ordered by the first number of each entry, like `day9` or `2021 => aoc_21`.
+fn register(content: &str, call: &Regex, item: &str) -> Result<String> {
+    let caps = call.captures(content).ok_or_else(|| anyhow!("No registration macro call found"))?;
+    let mut items: Vec<&str> = caps["list"].split(',').map(str::trim).filter(|s| !s.is_empty()).collect();
+
+    if items.contains(&item) {
+        return Err(anyhow!("{} is already registered", item));
+    }
+
+    items.push(item);
+    items.sort_by_key(|s| {
+        let digits: String = s.chars().skip_while(|c| !c.is_ascii_digit()).take_while(char::is_ascii_digit).collect();
+        digits.parse::<u32>().unwrap_or(u32::MAX)
+    });
+
+    let list = caps.name("list").unwrap();
+
+    Ok(format!("{}{}{}", &content[..list.start()], items.join(", "), &content[list.end()..]))
+}
+
+/// Last year whose modules can be told apart: they are named after the last two digits.
+pub const LAST_YEAR: u32 = 2099;
+
+/// A file to write, along with what it held before so a failed scaffold can be undone.
+struct Edit {
+    path: PathBuf,
+    content: String,
+    previous: Option<String>,
+}
+
+impl Edit {
+    fn create(path: PathBuf, content: String) -> Result<Self> {
+        if path.exists() {
+            return Err(anyhow!("{} already exists", path.to_string_lossy()));
+        }
+
+        Ok(Edit { path, content, previous: None })
+    }
+
+    fn register(path: PathBuf, call: &Regex, item: &str) -> Result<Self> {
+        let previous = std::fs::read_to_string(&path)
+            .with_context(|| format!("Unable to read {}", path.to_string_lossy()))?;
+        let content = register(&previous, call, item)
+            .with_context(|| format!("Unable to register {} in {}", item, path.to_string_lossy()))?;
+
+        Ok(Edit { path, content, previous: Some(previous) })
+    }
+
+    fn undo(&self) {
+        let _ = match &self.previous {
+            Some(previous) => std::fs::write(&self.path, previous),
+            None => std::fs::remove_file(&self.path),
+        };
+    }
+}
+
+/// Writes every edit, or none: on failure, the files already written are restored
+/// and the directories created for them removed.
+fn apply(edits: &[Edit]) -> Result<()> {
+    let mut new_dirs: Vec<&Path> = edits.iter()
+        .flat_map(|e| e.path.ancestors().skip(1).take_while(|dir| !dir.exists()))
+        .collect();
+    new_dirs.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+    new_dirs.dedup();
+
+    for (i, edit) in edits.iter().enumerate() {
+        let written = edit.path.parent().map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&edit.path, &edit.content))
+            .with_context(|| format!("Unable to write {}", edit.path.to_string_lossy()));
+
+        if let Err(e) = written {
+            edits[..=i].iter().rev().for_each(Edit::undo);
+            new_dirs.iter().for_each(|dir| { let _ = std::fs::remove_dir(dir); });
+
+            return Err(e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Generates the module of a day and registers it, creating the year module when
+/// it doesn't exist yet, along with an empty example and its answers file.
+/// Every change is checked before anything is written, and undone if a write fails.
+/// Returns the files created or changed.
+pub fn create(solutions_dir: &Path, examples_dir: &Path, date: &AocDate) -> Result<Vec<PathBuf>> {
+    lazy_static! {
+        static ref YEARS: Regex = Regex::new(r"(?m)^years!\(\s*(?P<list>[^)]*?)\s*\);").unwrap();
+        static ref DAYS: Regex = Regex::new(r"(?m)^solutions!\(\s*(?P<year>\d+)\s*=>\s*(?P<list>[^)]*?)\s*\);").unwrap();
+    }
+
+    date.validate()?;
+    if date.year > LAST_YEAR {
+        return Err(anyhow!("Can't scaffold {}: year modules are only named after years up to {}", date, LAST_YEAR));
+    }
+
+    let module = year_module(date.year);
+    let year_dir = solutions_dir.join(&module);
+    let year_mod = year_dir.join("mod.rs");
+    let day = format!("day{}", date.day);
+    let mut edits = vec![Edit::create(year_dir.join(format!("{}.rs", day)), render(date))?];
+
+    let example_dir = examples::day_dir(examples_dir, date);
+
+    for (name, content) in [("example.txt", ""), (examples::ANSWERS_FILE, EXAMPLE_ANSWERS)] {
+        let path = example_dir.join(name);
+
+        if !path.exists() {
+            edits.push(Edit::create(path, content.to_string())?);
+        }
+    }
+
+    if year_mod.exists() {
+        let edit = Edit::register(year_mod, &DAYS, &day)?;
+        let registered = edit.previous.as_deref().and_then(|content| DAYS.captures(content)).map(|caps| caps["year"].to_string());
+
+        if registered != Some(date.year.to_string()) {
+            return Err(anyhow!("{} doesn't hold the solutions of {}", module, date.year));
+        }
+        edits.push(edit);
+    } else {
+        edits.push(Edit::create(year_mod, format!("solutions!({} => {});\n", date.year, day))?);
+        edits.push(Edit::register(solutions_dir.join("mod.rs"), &YEARS, &format!("{} => {}", date.year, module))?);
+    }
+
+    apply(&edits)?;
+
+    Ok(edits.into_iter().map(|e| e.path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_render_template() {
+        let source = render(&AocDate { year: 2022, day: 7 });
+
+        assert!(source.contains("pub type TheDay = Day<YEAR, 7>;"));
+        assert!(source.contains("include_str!(\"../../../examples/2022/day7/example.txt\")"));
+        assert!(source.contains("pub fn test_aoc22_7_parse()"));
+        assert!(!source.contains("{{"));
+        assert_eq!(year_module(2021), "aoc_21");
+    }
+
+    #[test]
+    pub fn test_create_day() {
+        let root = std::env::temp_dir().join(format!("aoc-rs-scaffold-{}", std::process::id()));
+        let solutions = root.join("solutions");
+        let examples = root.join("examples");
+        std::fs::create_dir_all(solutions.join("aoc_21")).unwrap();
+        std::fs::write(solutions.join("mod.rs"), "years!(2020 => aoc_20, 2021 => aoc_21);\n").unwrap();
+        std::fs::write(solutions.join("aoc_21").join("mod.rs"), "solutions!(2021 => day1, day2, day10);\n").unwrap();
+        let read = |path: &[&str]| std::fs::read_to_string(path.iter().fold(solutions.clone(), |p, s| p.join(s))).unwrap();
+
+        create(&solutions, &examples, &AocDate { year: 2021, day: 9 }).unwrap();
+        assert_eq!(read(&["aoc_21", "mod.rs"]), "solutions!(2021 => day1, day2, day9, day10);\n");
+        assert!(read(&["aoc_21", "day9.rs"]).contains("Day<YEAR, 9>"));
+        assert!(examples.join("2021").join("day9").join("example.txt").exists());
+        assert!(create(&solutions, &examples, &AocDate { year: 2021, day: 9 }).is_err());
+
+        let changed = create(&solutions, &examples, &AocDate { year: 2022, day: 1 }).unwrap();
+        assert_eq!(changed.len(), 5);
+        assert_eq!(read(&["mod.rs"]), "years!(2020 => aoc_20, 2021 => aoc_21, 2022 => aoc_22);\n");
+        assert_eq!(read(&["aoc_22", "mod.rs"]), "solutions!(2022 => day1);\n");
+
+        assert!(create(&solutions, &examples, &AocDate { year: 2121, day: 1 }).is_err());
+        std::fs::write(solutions.join("aoc_22").join("mod.rs"), "solutions!(2122 => day1);\n").unwrap();
+        assert!(create(&solutions, &examples, &AocDate { year: 2022, day: 2 }).is_err());
+        assert!(!solutions.join("aoc_22").join("day2.rs").exists());
+
+        // Examples can't be written under a file: everything written before is undone.
+        let blocked = root.join("blocked");
+        std::fs::write(&blocked, "").unwrap();
+        assert!(create(&solutions, &blocked, &AocDate { year: 2023, day: 1 }).is_err());
+        assert_eq!(read(&["mod.rs"]), "years!(2020 => aoc_20, 2021 => aoc_21, 2022 => aoc_22);\n");
+        assert!(!solutions.join("aoc_23").exists());
+
+        let registry = solutions.join("mod.rs");
+        let edits = [
+            Edit { path: registry.clone(), content: "changed".to_string(), previous: Some(read(&["mod.rs"])) },
+            Edit { path: blocked.join("file"), content: String::new(), previous: None },
+        ];
+        assert!(apply(&edits).is_err());
+        assert_eq!(read(&["mod.rs"]), "years!(2020 => aoc_20, 2021 => aoc_21, 2022 => aoc_22);\n");
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
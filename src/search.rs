@@ -0,0 +1,262 @@
+use std::cmp::Reverse;
+use std::collections::{ BinaryHeap, HashMap, HashSet, VecDeque };
+use std::hash::Hash;
+use std::ops::Add;
+
+// Searches work on any node type, be it a grid `Pos` or a whole puzzle state. The graph
+// is implicit: `neighbors` lists the nodes reachable from a node in one move, along with
+// the cost of the move for the weighted searches.
+
+/// Nodes seen by a search, each one remembering the node it was first reached from.
+#[allow(dead_code)]
+struct Visited<N> {
+    nodes: Vec<N>,
+    parents: Vec<usize>,
+    index: HashMap<N, usize>,
+}
+
+#[allow(dead_code)]
+impl<N: Clone + Eq + Hash> Visited<N> {
+    fn new(start: N) -> Self {
+        Visited { nodes: vec![start.clone()], parents: vec![0], index: HashMap::from([(start, 0)]) }
+    }
+
+    /// Index of a node, and whether it was seen for the first time.
+    fn insert(&mut self, node: N, parent: usize) -> (usize, bool) {
+        if let Some(&i) = self.index.get(&node) {
+            return (i, false);
+        }
+
+        let i = self.nodes.len();
+        self.index.insert(node.clone(), i);
+        self.nodes.push(node);
+        self.parents.push(parent);
+
+        (i, true)
+    }
+
+    /// Nodes from the start to `end`, both included.
+    fn path(&self, end: usize) -> Vec<N> {
+        let mut path = vec![self.nodes[end].clone()];
+        let mut i = end;
+
+        while i != 0 {
+            i = self.parents[i];
+            path.push(self.nodes[i].clone());
+        }
+
+        path.reverse();
+        path
+    }
+}
+
+/// Shortest path, in number of moves, from `start` to the first node satisfying `goal`.
+#[allow(dead_code)]
+pub fn bfs<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I, mut goal: impl FnMut(&N) -> bool) -> Option<Vec<N>>
+where N: Clone + Eq + Hash, I: IntoIterator<Item = N> {
+    let mut visited = Visited::new(start);
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(i) = queue.pop_front() {
+        if goal(&visited.nodes[i]) {
+            return Some(visited.path(i));
+        }
+
+        for next in neighbors(&visited.nodes[i].clone()) {
+            if let (j, true) = visited.insert(next, i) {
+                queue.push_back(j);
+            }
+        }
+    }
+
+    None
+}
+
+/// Number of moves from `start` to every node reachable from it.
+#[allow(dead_code)]
+pub fn bfs_distances<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> HashMap<N, usize>
+where N: Clone + Eq + Hash, I: IntoIterator<Item = N> {
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        let distance = distances[&node] + 1;
+
+        for next in neighbors(&node) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), distance);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
+
+/// Some path, not necessarily the shortest, from `start` to the first node found satisfying `goal`.
+#[allow(dead_code)]
+pub fn dfs<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I, mut goal: impl FnMut(&N) -> bool) -> Option<Vec<N>>
+where N: Clone + Eq + Hash, I: IntoIterator<Item = N> {
+    let mut visited = Visited::new(start);
+    let mut stack = vec![0];
+
+    while let Some(i) = stack.pop() {
+        if goal(&visited.nodes[i]) {
+            return Some(visited.path(i));
+        }
+
+        for next in neighbors(&visited.nodes[i].clone()) {
+            if let (j, true) = visited.insert(next, i) {
+                stack.push(j);
+            }
+        }
+    }
+
+    None
+}
+
+/// Every node reachable from `start`, itself included.
+pub fn flood_fill<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> HashSet<N>
+where N: Clone + Eq + Hash, I: IntoIterator<Item = N> {
+    let mut filled = HashSet::from([start.clone()]);
+    let mut stack = vec![start];
+
+    while let Some(node) = stack.pop() {
+        for next in neighbors(&node) {
+            if filled.insert(next.clone()) {
+                stack.push(next);
+            }
+        }
+    }
+
+    filled
+}
+
+/// Splits `nodes` into groups connected by `neighbors`, in the order their first node comes in.
+/// Neighbours are expected to be symmetric and to stay within `nodes`.
+pub fn connected_components<N, I>(nodes: impl IntoIterator<Item = N>, mut neighbors: impl FnMut(&N) -> I) -> Vec<HashSet<N>>
+where N: Clone + Eq + Hash, I: IntoIterator<Item = N> {
+    let mut seen: HashSet<N> = HashSet::new();
+    let mut components = Vec::new();
+
+    for node in nodes {
+        if !seen.contains(&node) {
+            let component = flood_fill(node, &mut neighbors);
+            seen.extend(component.iter().cloned());
+            components.push(component);
+        }
+    }
+
+    components
+}
+
+/// Cheapest path from `start` to the first node satisfying `goal`, along with its cost.
+#[allow(dead_code)]
+pub fn dijkstra<N, C, I>(start: N, neighbors: impl FnMut(&N) -> I, goal: impl FnMut(&N) -> bool) -> Option<(Vec<N>, C)>
+where N: Clone + Eq + Hash, C: Copy + Ord + Default + Add<Output = C>, I: IntoIterator<Item = (N, C)> {
+    astar(start, neighbors, |_| C::default(), goal)
+}
+
+/// Cheapest path from `start` to the first node satisfying `goal`, along with its cost.
+/// `heuristic` estimates the remaining cost from a node; the path is the cheapest as long
+/// as it never overestimates, like the Manhattan distance on a grid.
+#[allow(dead_code)]
+pub fn astar<N, C, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where N: Clone + Eq + Hash, C: Copy + Ord + Default + Add<Output = C>, I: IntoIterator<Item = (N, C)> {
+    let mut visited = Visited::new(start);
+    let mut costs = vec![C::default()];
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&visited.nodes[0]), 0))]);
+
+    while let Some(Reverse((estimate, i))) = queue.pop() {
+        let cost = costs[i];
+
+        if estimate > cost + heuristic(&visited.nodes[i]) {
+            continue;
+        }
+
+        if goal(&visited.nodes[i]) {
+            return Some((visited.path(i), cost));
+        }
+
+        for (next, step) in neighbors(&visited.nodes[i].clone()) {
+            let next_cost = cost + step;
+            let (j, new) = visited.insert(next, i);
+
+            if new {
+                costs.push(next_cost);
+            } else if next_cost < costs[j] {
+                costs[j] = next_cost;
+                visited.parents[j] = i;
+            } else {
+                continue;
+            }
+
+            queue.push(Reverse((next_cost + heuristic(&visited.nodes[j]), j)));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{ self, Grid, Pos };
+
+    const MAZE: &str = "\
+        S.#.....\n\
+        .##.###.\n\
+        ....#E..\n\
+        .##.#.#.\n";
+
+    #[test]
+    pub fn test_unweighted_searches() {
+        let maze = Grid::parse_chars(MAZE).unwrap();
+        let open = |p: &Pos| maze.neighbors4(*p).filter(|&n| maze[n] != '#').collect::<Vec<_>>();
+        let end = (2, 5);
+
+        let path = bfs((0, 0), open, |&p| p == end).unwrap();
+        assert_eq!(path.len(), 16);
+        assert_eq!((path[0], path[15]), ((0, 0), end));
+        assert!(path.windows(2).all(|w| maze.neighbors4(w[0]).any(|n| n == w[1])));
+
+        assert_eq!(bfs_distances((0, 0), open)[&end], 15);
+        assert_eq!(dfs((0, 0), open, |&p| p == end).unwrap().last(), Some(&end));
+        assert_eq!(bfs((0, 0), open, |&p| p == (0, 2)), None);
+        assert_eq!(flood_fill((0, 0), open).len(), maze.cells().iter().filter(|&&c| c != '#').count());
+
+        let components = connected_components(
+            maze.positions().filter(|&p| maze[p] == '#'),
+            |p| maze.neighbors4(*p).filter(|&n| maze[n] == '#').collect::<Vec<_>>(),
+        );
+        assert_eq!(components.iter().map(HashSet::len).collect::<Vec<_>>(), vec![3, 5, 2, 1]);
+    }
+
+    #[test]
+    pub fn test_weighted_searches() {
+        let risk = Grid::parse_digits("1163751742\n1381373672\n2136511328\n3694931569\n7463417111").unwrap();
+        let end = (risk.rows() - 1, risk.columns() - 1);
+        let moves = |p: &Pos| risk.neighbors4(*p).map(|n| (n, risk[n] as u32)).collect::<Vec<_>>();
+
+        let path_risk = |path: &[Pos]| path.iter().skip(1).map(|&p| risk[p] as u32).sum::<u32>();
+
+        let (path, cost) = dijkstra((0, 0), moves, |&p| p == end).unwrap();
+        assert_eq!(cost, 29);
+        assert_eq!((path[0], path[path.len() - 1], path_risk(&path)), ((0, 0), end, 29));
+
+        let (path, cost) = astar((0, 0), moves, |&p| grid::manhattan(p, end) as u32, |&p| p == end).unwrap();
+        assert_eq!(cost, 29);
+        assert_eq!((path[0], path[path.len() - 1], path_risk(&path)), ((0, 0), end, 29));
+        assert!(path.windows(2).all(|w| grid::manhattan(w[0], w[1]) == 1));
+
+        // Implicit graph over numbers: reach 10 from 1 by adding 1 (cost 2) or doubling (cost 1).
+        let steps = |&n: &u32| [(n + 1, 2u32), (n * 2, 1)];
+        let (path, cost) = dijkstra(1u32, steps, |&n| n == 10).unwrap();
+        assert_eq!((path, cost), (vec![1, 2, 4, 5, 10], 5));
+    }
+}
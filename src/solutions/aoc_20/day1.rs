@@ -1,15 +1,11 @@
 use anyhow::{ Result, anyhow };
-use crate::common::{ Solution, Day };
+use crate::common::{ Solution, Day, parse };
 use super::YEAR;
 
 pub type TheDay = Day<YEAR, 1>;
 
-const SUM: u32 = 2020;
-
-fn str_to_ints_vec(str: &str) -> Vec<i32> {
-    str.lines().into_iter()
-    .map(|s| s.parse::<i32>().unwrap())
-    .collect()
+fn str_to_ints_vec(str: &str) -> Result<Vec<i32>> {
+    parse::values(str)
 }
 
 #[cfg(test)]
@@ -20,20 +16,27 @@ mod tests {
     pub fn test_parse_int_lines() {
         let input = "123\n247\n5\n24";
         let v = vec![123, 247, 5, 24];
-        assert_eq!(v, str_to_ints_vec(input));
+        assert_eq!(v, str_to_ints_vec(input).unwrap());
     }
 }
 
 impl Solution for TheDay {
-    type Output = i32;
+    type Parsed = Vec<i32>;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    const PART1_IMPLEMENTED: bool = false;
+    const PART2_IMPLEMENTED: bool = false;
 
-    fn part1(&self, input: &str) -> Result<Self::Output> {
-        let data = str_to_ints_vec(input);    
+    fn parse(&self, input: &str) -> Result<Self::Parsed> {
+        str_to_ints_vec(input)
+    }
 
+    fn part1(&self, _input: &Self::Parsed) -> Result<Self::Output1> {
         Err(anyhow!("Not implemented"))
     }
 
-    fn part2(&self, input: &str) -> Result<Self::Output> {
+    fn part2(&self, _input: &Self::Parsed) -> Result<Self::Output2> {
         Err(anyhow!("Not implemented"))
     }
 }
@@ -1,13 +1,11 @@
 use anyhow::Result;
-use crate::common::{ Solution, Day };
+use crate::common::{ Solution, Day, parse };
 use super::YEAR;
 
 pub type TheDay = Day<YEAR, 1>;
 
-fn str_to_ints_vec(str: &str) -> Vec<i32> {
-    str.lines().into_iter()
-    .map(|s| s.parse::<i32>().unwrap())
-    .collect()
+fn str_to_ints_vec(str: &str) -> Result<Vec<i32>> {
+    parse::values(str)
 }
 
 fn count_increasing(data: &[i32]) -> usize {
@@ -22,7 +20,7 @@ mod tests {
     pub fn test_parse_int_lines() {
         let input = "123\n247\n5\n24";
         let v = vec![123, 247, 5, 24];
-        assert_eq!(v, str_to_ints_vec(input));
+        assert_eq!(v, str_to_ints_vec(input).unwrap());
     }
 
 
@@ -47,17 +45,21 @@ mod tests {
 }
 
 impl Solution for TheDay {
-    type Output = usize;
+    type Parsed = Vec<i32>;
+    type Output1 = usize;
+    type Output2 = usize;
 
-    fn part1(&self, input: &str) -> Result<Self::Output> {
-        let data: Vec<i32> = str_to_ints_vec(input);
+    fn parse(&self, input: &str) -> Result<Self::Parsed> {
+        str_to_ints_vec(input)
+    }
 
-        Ok(count_increasing(&data))
+    fn part1(&self, input: &Self::Parsed) -> Result<Self::Output1> {
+        Ok(count_increasing(input))
     }
 
-    fn part2(&self, input: &str) -> Result<Self::Output> {
+    fn part2(&self, input: &Self::Parsed) -> Result<Self::Output2> {
         let data: Vec<i32> = 
-            str_to_ints_vec(input)
+            input
             .windows(3)
             .map(|w| w.iter().sum())
             .collect();
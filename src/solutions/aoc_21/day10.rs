@@ -1,5 +1,5 @@
-use anyhow::Result;
-use crate::common::{ Solution, Day };
+use anyhow::{ Result, anyhow };
+use crate::common::{ Solution, Day, parse };
 use super::YEAR;
 
 pub type TheDay = Day<YEAR, 10>;
@@ -11,52 +11,63 @@ mod tests {
 
     #[test]
     pub fn test_day21_10() {
-        assert_eq!(validate("{([(<{}[<>[]}>{[]{[(<()>"), Invalid('}'));
-        assert_eq!(validate("[[<[([]))<([[{}[[()]]]"), Invalid(')'));
-        assert_eq!(validate("[({(<(())[]>[[{[]{<()<>>"), Valid("[({([[{{".chars().collect::<Vec<char>>()));
+        assert_eq!(validate("{([(<{}[<>[]}>{[]{[(<()>").unwrap(), Invalid('}'));
+        assert_eq!(validate("[[<[([]))<([[{}[[()]]]").unwrap(), Invalid(')'));
+        assert_eq!(validate("[({(<(())[]>[[{[]{<()<>>").unwrap(), Valid("[({([[{{".chars().collect::<Vec<char>>()));
+        assert_eq!(validate(")").unwrap(), Invalid(')'));
+        assert_eq!(validate("(]x").unwrap(), Invalid(']'));
+        assert_eq!(validate("(x)").unwrap_err().to_string(), "unexpected character 'x' at column 2");
     }
 }
 
 #[derive(Debug, PartialEq, Eq)]
-enum Validation {
+pub enum Validation {
     Valid(Vec<char>),
     Invalid(char),
 }
 
-fn pair(c: char) -> char {
+fn pair(c: char) -> Option<char> {
     match c {
-        '(' => ')',
-        '[' => ']',
-        '{' => '}',
-        '<' => '>',
-        _ => panic!("Unexpected char: {}", c), 
+        '(' => Some(')'),
+        '[' => Some(']'),
+        '{' => Some('}'),
+        '<' => Some('>'),
+        _ => None,
     }
 }
 
-fn validate(s: &str) -> Validation {
+fn validate(s: &str) -> Result<Validation> {
     let mut stack: Vec<char> = Vec::new();
 
-    for c in s.chars() {
+    for (i, c) in s.chars().enumerate() {
         if "({[<".contains(c) {
             stack.push(c);
-        } else {
-            if pair(stack.pop().unwrap()) != c {
-                return Validation::Invalid(c);
+        } else if ")]}>".contains(c) {
+            if stack.pop().and_then(pair) != Some(c) {
+                return Ok(Validation::Invalid(c));
             }
+        } else {
+            return Err(anyhow!("unexpected character '{}' at column {}", c, i + 1));
         }
     }
-    Validation::Valid(stack) 
+    Ok(Validation::Valid(stack))
 }
 
 impl Solution for TheDay {
-    type Output = usize;
+    type Parsed = Vec<Validation>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed> {
+        parse::lines(input, validate)
+    }
 
-    fn part1(&self, input: &str) -> Result<Self::Output> {
+    fn part1(&self, input: &Self::Parsed) -> Result<Self::Output1> {
         use Validation::Invalid;
 
         Ok(
-            input.lines()
-                .map(|s| match validate(s) {
+            input.iter()
+                .map(|v| match v {
                     Invalid(')') => 3,
                     Invalid(']') => 57,
                     Invalid('}') => 1197,
@@ -67,18 +78,18 @@ impl Solution for TheDay {
         )
     }
 
-    fn part2(&self, input: &str) -> Result<Self::Output> {
-        let mut scores: Vec<usize> = input.lines()
-            .map(|l| {
-                match validate(l) {
+    fn part2(&self, input: &Self::Parsed) -> Result<Self::Output2> {
+        let mut scores: Vec<usize> = input.iter()
+            .map(|v| {
+                match v {
                     Validation::Valid(stack) => {
                         stack.iter()
                             .rev()
                             .map(|c| match pair(*c) {
-                                ')' => 1,
-                                ']' => 2,
-                                '}' => 3,
-                                '>' => 4,
+                                Some(')') => 1,
+                                Some(']') => 2,
+                                Some('}') => 3,
+                                Some('>') => 4,
                                  _ => 0 
                              })
                             .fold(0, |acc, v| 5 * acc + v)
@@ -1,79 +1,28 @@
 use anyhow::Result;
 use crate::common::{ Solution, Day };
+use crate::grid::{ Grid, Pos };
 use super::YEAR;
 
 use std::collections::HashSet;
 
 pub type TheDay = Day<YEAR, 11>;
 
-struct EnergyMap {
-    energy: Vec<u8>,
-    columns: usize,
-    rows: usize,
+#[derive(Clone)]
+pub struct EnergyMap {
+    energy: Grid<u8>,
 }
 
 impl EnergyMap {
-    fn from_string(data: &str) -> Self {
-        let columns = data.trim().lines().next().unwrap().len();
-        let energy: Vec<u8> = 
-            data.lines()
-                .filter(|s| !s.is_empty())
-                .flat_map(|l| l.trim().chars().map(|l| l.to_digit(10).unwrap() as u8))
-                .collect();
-        let rows = energy.len() / columns; 
-
-        Self { energy, columns, rows }
-    }
-
-    fn from_index(&self, index: usize) -> (usize, usize) { // (row, column)
-        (index / self.columns, index % self.columns)
-    }
-
-    fn to_index(&self, row: usize, col: usize) -> usize {
-        use std::cmp::{min, max};
-
-        let r = min(max(0, row), self.rows - 1);
-        let c = min(max(0, col), self.columns - 1);
-
-        r * self.columns + c
-    }
-
-    fn neighbors(&self, index: usize) -> Vec<usize> {
-        let last_col: usize = self.columns - 1;
-        let last_row: usize = self.rows - 1;
-
-        match self.from_index(index) {
-            // Corners:
-            // - NW
-            (0, 0) => vec![self.to_index(0, 1), self.to_index(1, 1), self.to_index(1, 0)],
-            // - SW
-            (r, 0) if r == last_row => vec![self.to_index(r - 1, 0), self.to_index(r - 1, 1), self.to_index(r, 1)],
-            // - NE
-            (0, c) if c == last_col => vec![self.to_index(0, c - 1), self.to_index(1, c), self.to_index(1, c - 1)],
-            // - SE
-            (r, c) if r == last_row && c == last_col => vec![self.to_index(r, c - 1), self.to_index(r - 1, c - 1), self.to_index(r - 1, c)],
-
-            // Edges:
-            // - W
-            (r, 0) => vec![self.to_index(r - 1, 0), self.to_index(r - 1, 1), self.to_index(r, 1), self.to_index(r + 1, 1), self.to_index(r + 1, 0)],
-            // - N
-            (0, c) => vec![self.to_index(0, c - 1), self.to_index(0, c + 1), self.to_index(1, c + 1), self.to_index(1, c), self.to_index(1, c - 1)],
-            // - E 
-            (r, c) if c == last_col => vec![self.to_index(r, c - 1), self.to_index(r - 1, c - 1), self.to_index(r - 1, c), self.to_index(r + 1, c), self.to_index(r + 1, c - 1)],
-            // - S
-            (r, c) if r == last_row => vec![self.to_index(r, c - 1), self.to_index(r - 1, c - 1), self.to_index(r - 1, c), self.to_index(r - 1, c + 1), self.to_index(r, c + 1)],
-            // Inner
-            (r, c) => vec![self.to_index(r, c - 1), self.to_index(r - 1, c - 1), self.to_index(r - 1, c), self.to_index(r - 1, c + 1),
-                           self.to_index(r, c + 1), self.to_index(r + 1, c + 1), self.to_index(r + 1, c), self.to_index(r + 1, c - 1)]    
-        }
+    fn from_string(data: &str) -> Result<Self> {
+        Ok(Self { energy: Grid::parse_digits(data)? })
     }
 
     fn step(&mut self) -> usize {
-        let mut flashed: HashSet<usize> = HashSet::new();
-        let mut to_scan: Vec<usize> = (0..self.energy.len()).collect();
+        let mut flashed: HashSet<Pos> = HashSet::new();
+        let mut to_scan: Vec<Pos> = self.energy.positions().collect();
 
-        while to_scan.len() > 0 {
-            let mut scan_neighbors: Vec<usize> = Vec::new();
+        while !to_scan.is_empty() {
+            let mut scan_neighbors: Vec<Pos> = Vec::new();
 
             for i in to_scan.drain(..) {
                 if !flashed.contains(&i) {
@@ -88,7 +37,7 @@ impl EnergyMap {
                 }
             }            
             to_scan = scan_neighbors.iter()
-                .flat_map(|idx| self.neighbors(*idx))
+                .flat_map(|pos| self.energy.neighbors8(*pos))
                 .filter(|idx| !flashed.contains(idx))
                 .collect();
         }
@@ -99,24 +48,21 @@ impl EnergyMap {
 
 impl std::fmt::Display for EnergyMap {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        for (i, e) in self.energy.iter().enumerate() {
-            write!(f, "{}", e)?;
-            let idx = i + 1;
-            if idx % self.columns == 0 {
-
-                write!(f, "\n")?;
-            }
-        }
-
-        Ok(())
+        write!(f, "{}", self.energy)
     }
 }
 
 impl Solution for TheDay {
-    type Output = usize;
+    type Parsed = EnergyMap;
+    type Output1 = usize;
+    type Output2 = usize;
 
-    fn part1(&self, input: &str) -> Result<Self::Output> {
-        let mut m = EnergyMap::from_string(input);
+    fn parse(&self, input: &str) -> Result<Self::Parsed> {
+        EnergyMap::from_string(input)
+    }
+
+    fn part1(&self, input: &Self::Parsed) -> Result<Self::Output1> {
+        let mut m = input.clone();
         let mut flashed = 0;
 
         for _ in 0..100 {
@@ -126,11 +72,11 @@ impl Solution for TheDay {
         Ok(flashed)
     }
 
-    fn part2(&self, input: &str) -> Result<Self::Output> {
-        let mut m = EnergyMap::from_string(input);
+    fn part2(&self, input: &Self::Parsed) -> Result<Self::Output2> {
+        let mut m = input.clone();
         let mut step = 0;
 
-        while !m.energy.iter().all(|e| *e == 0u8) {
+        while !m.energy.cells().iter().all(|e| *e == 0u8) {
             m.step();
 
             step += 1;
@@ -138,4 +84,26 @@ impl Solution for TheDay {
 
         Ok(step)
     }
+
+    fn solve_both(&self, input: &Self::Parsed) -> Option<Result<(Self::Output1, Self::Output2)>> {
+        let mut m = input.clone();
+        let mut flashed = 0;
+        let mut step = 0;
+        let mut synchronized = None;
+
+        while step < 100 || synchronized.is_none() {
+            if synchronized.is_none() && m.energy.cells().iter().all(|e| *e == 0u8) {
+                synchronized = Some(step);
+            }
+
+            let n = m.step();
+            step += 1;
+
+            if step <= 100 {
+                flashed += n;
+            }
+        }
+
+        Some(Ok((flashed, synchronized.unwrap())))
+    }
 }
@@ -1,40 +1,20 @@
 use anyhow::Result;
-use crate::common::{ Solution, Day };
-use regex::Regex;
-use lazy_static::lazy_static;
+use crate::common::{ Solution, Day, parse };
 use super::YEAR;
 
 pub type TheDay = Day<YEAR, 2>;
 
-#[derive(Debug, PartialEq, Eq)]
-enum Command {
-    Up(i32),
-    Down(i32),
-    Forward(i32),
-}
-
-impl Command {
-    fn from_string(s: &str) -> Command {
-        lazy_static! {
-            static ref PATTERN: Regex = Regex::new(r"^(forward|down|up)\s(\d+)$").unwrap();
-        }
-
-        let caps = PATTERN.captures(s).unwrap(); 
-        let arg = caps.get(2).map_or(0, |m| m.as_str().parse::<i32>().unwrap());
-    
-        match caps.get(1).map_or("", |m| m.as_str()) {
-            "forward" => Command::Forward(arg),
-            "up" => Command::Up(arg),
-            "down" => Command::Down(arg),
-            _ => panic!("Unexpected input"),
-        }
+parse::regex_record! {
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Command {
+        Up(n: i32) = r"^up\s+(?P<n>\d+)$",
+        Down(n: i32) = r"^down\s+(?P<n>\d+)$",
+        Forward(n: i32) = r"^forward\s+(?P<n>\d+)$",
     }
 }
 
-fn parse_input(input: &str) -> Vec<Command> {
-    input.lines().into_iter()
-    .map(|s| Command::from_string(s))
-    .collect()
+fn parse_input(input: &str) -> Result<Vec<Command>> {
+    parse::lines(input, str::parse)
 }
 
 #[cfg(test)]
@@ -43,20 +23,28 @@ mod tests {
 
     #[test]
     pub fn test_command_parsing() {
-        assert_eq!(Command::from_string("forward 3"), Command::Forward(3));
-        assert_eq!(Command::from_string("up 10"), Command::Up(10));
-        assert_eq!(Command::from_string("down 5"), Command::Down(5));
+        assert_eq!("forward 3".parse::<Command>().unwrap(), Command::Forward(3));
+        assert_eq!("up 10".parse::<Command>().unwrap(), Command::Up(10));
+        assert_eq!("down 5".parse::<Command>().unwrap(), Command::Down(5));
+        assert!("backward 5".parse::<Command>().is_err());
+        assert_eq!(format!("{:#}", parse_input("up 1\ndown x").unwrap_err()), "line 2: 'down x' doesn't match any Command");
     }
 }
 
 impl Solution for TheDay {
-    type Output = i32;
+    type Parsed = Vec<Command>;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed> {
+        parse_input(input)
+    }
 
-    fn part1(&self, input: &str) -> Result<Self::Output> {
+    fn part1(&self, input: &Self::Parsed) -> Result<Self::Output1> {
         let mut horiz = 0;
         let mut depth = 0;
         
-        for cmd in parse_input(input).iter() {
+        for cmd in input {
             match cmd {
                 Command::Forward(n) => horiz += n,
                 Command::Up(n) => depth -= n,
@@ -67,12 +55,12 @@ impl Solution for TheDay {
         Ok(horiz * depth)
     }
 
-    fn part2(&self, input: &str) -> Result<Self::Output> {
+    fn part2(&self, input: &Self::Parsed) -> Result<Self::Output2> {
         let mut horiz = 0;
         let mut depth = 0;
         let mut aim = 0;
         
-        for cmd in parse_input(input).iter() {
+        for cmd in input {
             match cmd {
                 Command::Forward(n) => {
                     horiz += n;
@@ -1,35 +1,36 @@
-use anyhow::Result;
-use crate::common::{ Solution, Day };
+use anyhow::{ Result, anyhow };
+use crate::common::{ Solution, Day, parse };
 use super::YEAR;
 
 pub type TheDay = Day<YEAR, 3>;
 
 fn bin_vec_to_num(v: &[u32]) -> u32 {
     let msb_idx = v.len() - 1;
-    v.iter().enumerate().fold(0u32, |acc, (i, b)| acc | (b << msb_idx - i))
+    v.iter().enumerate().fold(0u32, |acc, (i, b)| acc | (b << (msb_idx - i)))
 }
 
-fn str_to_bin_vec(s: &str) -> Vec<u32> {
-    s.chars().map(|c| c.to_digit(2).unwrap()).collect()
+/// Diagnostic report numbers together with their width in bits.
+pub struct Diagnostic {
+    width: usize,
+    numbers: Vec<u32>,
 }
 
-fn load_popcount(input: &str) -> (u32, Vec<u32>) {
-    let mut lines = input.lines();
-    if let Some(init_str) = lines.next() {
-        let mut total_len = 1;
-        let mut bin_vec = str_to_bin_vec(init_str);
+impl Diagnostic {
+    fn from_string(input: &str) -> Result<Self> {
+        let width = input.lines().next().map_or(0, |s| s.trim().len());
+        let numbers = parse::lines(input, |s| {
+            u32::from_str_radix(s, 2).map_err(|e| anyhow!("invalid binary number '{}': {}", s, e))
+        })?;
 
-        for s in lines {
-            for (i, b) in s.chars().map(|c| c.to_digit(2).unwrap()).enumerate() {
-                bin_vec[i] += b
-            }
+        Ok(Diagnostic { width, numbers })
+    }
 
-            total_len += 1;
-        }
+    fn most_common_bits(&self) -> Vec<u32> {
+        let threshold = self.numbers.len() as u32 / 2;
 
-        (total_len, bin_vec)
-    } else {
-        panic!("Unexpected empty input");
+        (0..self.width).rev()
+            .map(|bit| if popcount(bit, &self.numbers) > threshold { 1 } else { 0 })
+            .collect()
     }
 }
 
@@ -49,14 +50,12 @@ mod tests {
 
     #[test]
     pub fn test_aoc21_3_p1() {
-        let input = "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010";
-        let (n, pop_counts) = load_popcount(input);
-        let threshold = n / 2;
-
-        let most_common_bits: Vec<u32> = pop_counts.iter().map(|n_ones| if n_ones > &threshold { 1u32 } else { 0u32 }).collect();
+        let input = include_str!("../../../examples/2021/day3/example.txt");
+        let diagnostic = Diagnostic::from_string(input).unwrap();
+        let most_common_bits = diagnostic.most_common_bits();
 
         let gamma = bin_vec_to_num(&most_common_bits);
-        let epsilon = !gamma & ((1 << pop_counts.len()) - 1);
+        let epsilon = !gamma & ((1 << diagnostic.width) - 1);
 
         assert_eq!(most_common_bits, vec![1, 0, 1, 1, 0]);
         assert_eq!(22, bin_vec_to_num(&most_common_bits));
@@ -65,30 +64,36 @@ mod tests {
 
     #[test]
     pub fn test_aoc21_3_p2() {
-        let input = "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010";
-        assert!(true);
+        let input = include_str!("../../../examples/2021/day3/example.txt");
+        let nums = Diagnostic::from_string(input).unwrap().numbers;
+
+        assert_eq!(popcount(4, &nums), 7);
+        assert_eq!(popcount(0, &nums), 5);
     }
 }
 
 impl Solution for TheDay {
-    type Output = u32;
+    type Parsed = Diagnostic;
+    type Output1 = u32;
+    type Output2 = u32;
 
-    fn part1(&self, input: &str) -> Result<Self::Output> {
-        let (n, pop_counts) = load_popcount(input);
-        let threshold = n / 2;
+    fn parse(&self, input: &str) -> Result<Self::Parsed> {
+        Diagnostic::from_string(input)
+    }
 
-        let most_common_bits: Vec<u32> = pop_counts.iter().map(|n_ones| if n_ones > &threshold { 1 } else { 0 }).collect();
+    fn part1(&self, input: &Self::Parsed) -> Result<Self::Output1> {
+        let most_common_bits = input.most_common_bits();
 
         let gamma = bin_vec_to_num(&most_common_bits);
-        let epsilon = !gamma & ((1 << pop_counts.len()) - 1);
+        let epsilon = !gamma & ((1 << input.width) - 1);
 
         Ok(gamma * epsilon)   
     }
 
-    fn part2(&self, input: &str) -> Result<Self::Output> {
-        let n_bits = 12;
-        let mut oxygen_nums: Vec<u32> = input.lines().map(|s| u32::from_str_radix(s, 2).unwrap()).collect();
-        let mut co2_nums: Vec<u32> = input.lines().map(|s| u32::from_str_radix(s, 2).unwrap()).collect();
+    fn part2(&self, input: &Self::Parsed) -> Result<Self::Output2> {
+        let n_bits = input.width;
+        let mut oxygen_nums: Vec<u32> = input.numbers.clone();
+        let mut co2_nums: Vec<u32> = input.numbers.clone();
  
         let mut oxygen_val = 0u32;
         let mut co2_val = 0u32;
@@ -1,8 +1,7 @@
-use anyhow::Result;
-use crate::common::{ Solution, Day };
+use anyhow::{ Result, anyhow };
+use crate::common::{ Solution, Day, parse };
 use super::YEAR;
 
-use regex::Regex;
 use std::collections::HashSet;
 
 pub type TheDay = Day<YEAR, 4>;
@@ -10,24 +9,35 @@ pub type TheDay = Day<YEAR, 4>;
 const SIZE: usize = 5;
 type Slice = [u8; SIZE];
 
+#[derive(Clone)]
 struct Board {
     slices: [Slice; SIZE*2],
 }
 
 impl Board {
-    fn from_string(str_board: &[&str]) -> Self {
+    fn from_string(block: &parse::Block) -> Result<Self> {
         let mut slices = [[0u8; SIZE]; SIZE*2];
-        let split_regex = Regex::new(r"\s+").expect("Invalid regex syntax"); 
+        let rows: Vec<Vec<u8>> = block.parse(|line| {
+            let row = parse::whitespace_separated(line)?;
+
+            if row.len() != SIZE {
+                return Err(anyhow!("expected {} numbers, got {}", SIZE, row.len()));
+            }
+            Ok(row)
+        })?;
+
+        if rows.len() != SIZE {
+            return Err(anyhow!("board at line {} has {} rows instead of {}", block.line, rows.len(), SIZE));
+        }
 
         for row in 0..SIZE {
-            let str_row: Vec<u8> = split_regex.split(str_board[row].trim()).into_iter().map(|v| v.parse::<u8>().unwrap()).collect();
             for col in 0..SIZE {
-                slices[row][col] = str_row[col];
-                slices[col + SIZE][row] = str_row[col];
+                slices[row][col] = rows[row][col];
+                slices[col + SIZE][row] = rows[row][col];
              }
         }
 
-        Board { slices }
+        Ok(Board { slices })
     }
 
     fn check(&self, draws: &HashSet<u8>) -> Option<u32> {
@@ -58,7 +68,8 @@ impl Board {
     }
 }
 
-struct DayInput {
+#[derive(Clone)]
+pub struct DayInput {
     lots_pool: Vec<u8>,
     boards: Vec<Board>,
     current_draws: HashSet<u8>,
@@ -66,24 +77,12 @@ struct DayInput {
 }
 
 impl DayInput {
-    fn from_string(input: &str) -> Self {
-        let mut lines = input.lines();
-        let first_line = lines.next().unwrap();
-        let mut lots_pool: Vec<u8> = first_line.split(",").map(|s| s.parse::<u8>().unwrap()).collect();
-        let mut boards: Vec<Board> = Vec::new();
-
-        while let Some(row) = lines.next() {
-            if row.is_empty() {
-                let board_data: Vec<&str> = (&mut lines).take(SIZE).collect();
-                boards.push(Board::from_string(&board_data));
-            }
-        }
+    fn from_string(input: &str) -> Result<Self> {
+        let sections = parse::Sections::parse(input)?;
+        let lots_pool: Vec<u8> = sections.header.single(|line| parse::separated(line, ','))?;
+        let boards = sections.blocks.iter().map(Board::from_string).collect::<Result<Vec<Board>>>()?;
 
-        let mut current_draws: HashSet<u8> = HashSet::from_iter(lots_pool.drain(0..3));
-        let mut last_draw = lots_pool.remove(0);
-        current_draws.insert(last_draw);
- 
-        DayInput { lots_pool, boards, current_draws, last_draw }
+        Ok(DayInput { lots_pool, boards, current_draws: HashSet::new(), last_draw: 0 })
     }
 
     fn next_draw(&mut self) -> u8 {
@@ -97,141 +96,69 @@ impl DayInput {
 mod tests {
     use super::*;
 
-    static TEST_INPUT: &str =
-    r###"7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
-
-        22 13 17 11  0
-         8  2 23  4 24
-        21  9 14 16  7
-         6 10  3 18  5
-         1 12 20 15 19
-
-         3 15  0  2 22
-         9 18 13 17  5
-        19  8  7 25 23
-        20 11 10 24  4
-        14 21 16 12  6
-
-        14 21 17 24  4
-        10 16 15  9 19
-        18  8 23 26 20
-        22 11 13  6  5
-         2  0 12  3  7
-    "###;
+    static TEST_INPUT: &str = include_str!("../../../examples/2021/day4/example.txt");
 
     #[test]
     pub fn test_aoc21_4_data_load() {
-        let test_data = DayInput::from_string(TEST_INPUT);
+        let test_data = DayInput::from_string(TEST_INPUT).unwrap();
 
         assert_eq!(test_data.lots_pool.len(), 27);
         assert_eq!(test_data.boards.len(), 3);
         assert_eq!(test_data.boards[2].slices[0][2], 17u8);
         assert_eq!(test_data.boards[2].slices[2*SIZE - 1][2], 20u8);
-    }
-
-    #[test]
-    pub fn test_aoc21_4_p1() {
-        let mut test_data = DayInput::from_string(TEST_INPUT);
 
-        let mut current_lots: HashSet<u8> = HashSet::from_iter(test_data.lots_pool.drain(0..3));
-        let mut result: u32 = 0;
-        let mut last_draw = test_data.lots_pool.remove(0);
-        current_lots.insert(last_draw);
+        assert_eq!(
+            format!("{:#}", DayInput::from_string(&TEST_INPUT.replacen(" 8", " x", 1)).map(|_| ()).unwrap_err()),
+            "line 4: column 2: invalid value 'x': invalid digit found in string"
+        );
 
-        loop {
-            let winner_sum = test_data.boards.iter().map(|b| b.check(&current_lots)).find(|e| e.is_some());
-            
-            if let Some(Some(sum)) = winner_sum {
-                result = sum * last_draw as u32;
-                break;
-            } else if test_data.lots_pool.is_empty() {
-                log::error!("No winner board were found"); 
-                break;
-            } else {
-                last_draw = test_data.lots_pool.remove(0);
-                current_lots.insert(last_draw);
-                continue;
-            }
-        }
+        let indented = format!("\n  {}\n\n\n", TEST_INPUT.replace('\n', "   \n  "));
+        let reparsed = DayInput::from_string(&indented).unwrap();
+        assert_eq!(reparsed.lots_pool, test_data.lots_pool);
+        assert_eq!(reparsed.boards.len(), 3);
+        assert_eq!(reparsed.boards[2].slices, test_data.boards[2].slices);
 
-        assert_eq!(last_draw, 24);
-        assert_eq!(result, 4512);
+        assert!(DayInput::from_string(&TEST_INPUT.replacen("\n\n", "\n", 1)).is_err());
     }
 
     #[test]
-    pub fn test_aoc21_4_p2() {
-        let mut data = DayInput::from_string(TEST_INPUT);
+    pub fn test_aoc21_4_solve_both() {
+        let data = DayInput::from_string(TEST_INPUT).unwrap();
+        let (first, last) = TheDay::default().solve_both(&data).unwrap().unwrap();
 
-        let mut result: u32 = 0;
-        let mut winner_board_idx: usize = 0;
-
-        loop {
-            let not_winner_boards_indices: Vec<usize> = data.boards.iter()
-                .map(|b| b.check(&data.current_draws))
-                .enumerate().filter(|(_, e)| e.is_none())
-                .map(|(i, _)| i)
-                .collect();
-
-            if not_winner_boards_indices.len() == 1 {
-                winner_board_idx = not_winner_boards_indices[0];
-                break;
-            } else {
-                data.next_draw();
-            }
-        }
-
-        loop {
-            if let Some(sum) = data.boards[winner_board_idx].check(&data.current_draws) {
-                result = sum * data.last_draw as u32;
-                break;
-            } else if data.lots_pool.is_empty() {
-                log::error!("Finished without reaching expected condition");
-                break;
-            } else {
-                data.next_draw();
-            }
-        }
-
-        assert_eq!(data.last_draw, 13);
-        assert_eq!(result, 1924);
+        assert_eq!((first, last), (4512, 1924));
+        assert_eq!(first, TheDay::default().part1(&data).unwrap());
+        assert_eq!(last, TheDay::default().part2(&data).unwrap());
     }
 }
 
 impl Solution for TheDay {
-    type Output = u32;
+    type Parsed = DayInput;
+    type Output1 = u32;
+    type Output2 = u32;
 
-    fn part1(&self, input: &str) -> Result<Self::Output> {
-        let mut data = DayInput::from_string(input);
+    fn parse(&self, input: &str) -> Result<Self::Parsed> {
+        DayInput::from_string(input)
+    }
 
-        let mut current_lots: HashSet<u8> = HashSet::from_iter(data.lots_pool.drain(0..3));
-        let mut result: u32 = 0;
-        let mut last_draw = data.lots_pool.remove(0);
-        current_lots.insert(last_draw);
+    fn part1(&self, input: &Self::Parsed) -> Result<Self::Output1> {
+        let mut data = input.clone();
 
-        loop {
-            let winner_sum = data.boards.iter().map(|b| b.check(&current_lots)).find(|e| e.is_some());
-            
-            if let Some(Some(sum)) = winner_sum {
-                result = sum * last_draw as u32;
-                break;
-            } else if data.lots_pool.is_empty() {
-                log::error!("No winner board were found"); 
-                break;
-            } else {
-                last_draw = data.lots_pool.remove(0);
-                current_lots.insert(last_draw);
-                continue;
+        while !data.lots_pool.is_empty() {
+            let draw = data.next_draw();
+
+            if let Some(sum) = data.boards.iter().find_map(|b| b.check(&data.current_draws)) {
+                return Ok(sum * draw as u32);
             }
         }
 
-        Ok(result)
+        Err(anyhow!("No winner board were found"))
     }
 
-    fn part2(&self, input: &str) -> Result<Self::Output> {
-        let mut data = DayInput::from_string(input);
+    fn part2(&self, input: &Self::Parsed) -> Result<Self::Output2> {
+        let mut data = input.clone();
 
-        let mut result: u32 = 0;
-        let mut winner_board_idx: usize = 0;
+        let winner_board_idx: usize;
 
         loop {
             let not_winner_boards_indices: Vec<usize> = data.boards.iter()
@@ -243,23 +170,49 @@ impl Solution for TheDay {
             if not_winner_boards_indices.len() == 1 {
                 winner_board_idx = not_winner_boards_indices[0];
                 break;
+            } else if data.lots_pool.is_empty() {
+                return Err(anyhow!("No single last winner board was found"));
             } else {
                 data.next_draw();
             }
         }
 
-        loop {
+        while !data.lots_pool.is_empty() {
+            data.next_draw();
+
             if let Some(sum) = data.boards[winner_board_idx].check(&data.current_draws) {
-                result = sum * data.last_draw as u32;
-                break;
-            } else if data.lots_pool.is_empty() {
-                log::error!("Finished without reaching expected condition");
-                break;
-            } else {
-                data.next_draw();
+                return Ok(sum * data.last_draw as u32);
+            }
+        }
+
+        Err(anyhow!("Last board never wins"))
+    }
+
+    fn solve_both(&self, input: &Self::Parsed) -> Option<Result<(Self::Output1, Self::Output2)>> {
+        let mut data = input.clone();
+        let mut won = vec![false; data.boards.len()];
+        let mut first_score: Option<u32> = None;
+        let mut last_score: Option<u32> = None;
+
+        while !data.lots_pool.is_empty() && !won.iter().all(|w| *w) {
+            let draw = data.next_draw();
+
+            for (i, board) in data.boards.iter().enumerate() {
+                if won[i] {
+                    continue;
+                }
+
+                if let Some(sum) = board.check(&data.current_draws) {
+                    won[i] = true;
+                    first_score.get_or_insert(sum * draw as u32);
+                    last_score = Some(sum * draw as u32);
+                }
             }
         }
 
-        Ok(result)
+        Some(match (first_score, last_score) {
+            (Some(first), Some(last)) => Ok((first, last)),
+            _ => Err(anyhow!("No winner board were found")),
+        })
     }
 }
@@ -1,23 +1,25 @@
 use anyhow::Result;
-use crate::common::{ Solution, Day };
+use crate::common::{ Solution, Day, parse };
 use super::YEAR;
 
 pub type TheDay = Day<YEAR, 5>;
 
-use regex::Regex;
-use lazy_static::lazy_static;
 use std::collections::HashMap;
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-struct Point {
-    x: u32,
-    y: u32
+parse::regex_record! {
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+    struct Point = r"^(?P<x>\d+),(?P<y>\d+)$" {
+        x: u32,
+        y: u32
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct Segment {
-    begin: Point,
-    end: Point,
+parse::regex_record! {
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct Segment = r"^(?P<begin>\d+,\d+)\s+->\s+(?P<end>\d+,\d+)$" {
+        begin: Point,
+        end: Point,
+    }
 }
 
 impl Point {
@@ -35,21 +37,6 @@ impl Point {
 }
 
 impl Segment {
-    fn from_string(s: &str) -> Self {
-        lazy_static! {
-            static ref PATTERN: Regex = Regex::new(r"^(\d+),(\d+)\s+->\s+(\d+),(\d+)$").unwrap();
-        }
-
-        let caps = PATTERN.captures(s).unwrap();
-
-        let x1 = caps.get(1).map_or(0, |m| m.as_str().parse::<u32>().unwrap());
-        let y1 = caps.get(2).map_or(0, |m| m.as_str().parse::<u32>().unwrap());
-        let x2 = caps.get(3).map_or(0, |m| m.as_str().parse::<u32>().unwrap());
-        let y2 = caps.get(4).map_or(0, |m| m.as_str().parse::<u32>().unwrap());
-
-        Segment { begin: Point { x: x1, y: y1 }, end: Point { x: x2, y: y2 } }
-    }
-
     fn is_horizontal(&self) -> bool {
         self.begin.is_on_same_horizontal(&self.end)
     }
@@ -125,10 +112,10 @@ mod tests {
 
     #[test]
     pub fn test_aoc21_5_segment_iterator() {
-        let horizontal = Segment::from_string("12,5 -> 14,5");
-        let vertical = Segment::from_string("3,4 -> 3,2");
-        let diag1 = Segment::from_string("3,3 -> 0,0");
-        let diag2 = Segment::from_string("3,3 -> 5,1");
+        let horizontal: Segment = "12,5 -> 14,5".parse().unwrap();
+        let vertical: Segment = "3,4 -> 3,2".parse().unwrap();
+        let diag1: Segment = "3,3 -> 0,0".parse().unwrap();
+        let diag2: Segment = "3,3 -> 5,1".parse().unwrap();
         let mut h_trace = horizontal.trace();
 
         assert_eq!(h_trace.next(), Some(Point::new(12, 5)));
@@ -157,7 +144,7 @@ mod tests {
     }
 }
 
-fn compute_segment_intersects(segments: &[Segment]) -> usize {
+fn compute_segment_intersects<'a>(segments: impl IntoIterator<Item = &'a Segment>) -> usize {
     let mut reg: HashMap<Point, u32> = HashMap::new();
 
     for seg in segments {
@@ -175,22 +162,23 @@ fn compute_segment_intersects(segments: &[Segment]) -> usize {
 }
 
 impl Solution for TheDay {
-    type Output = usize;
+    type Parsed = Vec<Segment>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed> {
+        parse::lines(input, str::parse)
+    }
 
-    fn part1(&self, input: &str) -> Result<Self::Output> {
-        let segments: Vec<Segment> = input.lines()
-            .map(|s| Segment::from_string(s))
+    fn part1(&self, input: &Self::Parsed) -> Result<Self::Output1> {
+        let segments: Vec<&Segment> = input.iter()
             .filter(|seg| seg.is_vertical() || seg.is_horizontal())
             .collect();
 
-        Ok(compute_segment_intersects(&segments))
+        Ok(compute_segment_intersects(segments))
     }
 
-    fn part2(&self, input: &str) -> Result<Self::Output> {
-        let segments: Vec<Segment> = input.lines()
-            .map(|s| Segment::from_string(s))
-            .collect();
-
-        Ok(compute_segment_intersects(&segments))
+    fn part2(&self, input: &Self::Parsed) -> Result<Self::Output2> {
+        Ok(compute_segment_intersects(input))
     }
 }
@@ -1,5 +1,5 @@
-use anyhow::Result;
-use crate::common::{ Solution, Day };
+use anyhow::{ Result, anyhow };
+use crate::common::{ Solution, Day, parse };
 use super::YEAR;
 
 pub type TheDay = Day<YEAR, 6>;
@@ -10,7 +10,7 @@ mod tests {
 
     #[test]
     pub fn test_aoc21_6() {
-        let mut population: Vec<u8> = "3,4,3,1,2".split(",").map(|s| s.parse::<u8>().unwrap()).collect();
+        let mut population: Vec<u8> = parse::separated("3,4,3,1,2", ',').unwrap();
 
         for _ in 0..80 {
             let mut to_extend: usize = 0;
@@ -24,54 +24,69 @@ mod tests {
                 }
             }
 
-            for _ in 0..to_extend {
-                population.push(8);
-            }
+            population.resize(population.len() + to_extend, 8);
         }
 
-        let mut agg = Aggregate::from_string("3,4,3,1,2");
+        let mut agg = Aggregate::from_string("3,4,3,1,2").unwrap();
  
         assert_eq!(population.len(), agg.simulate(80) as usize);
+        assert_eq!(agg.simulate(256), Aggregate::from_string("3,4,3,1,2").unwrap().simulate(256));
+        assert!(Aggregate::from_string("3,9").is_err());
     }
 }
 
-struct Aggregate {
-    life_times: [u64; 9]
+#[derive(Clone)]
+pub struct Aggregate {
+    life_times: [u64; 9],
+    day: usize,
 }
 
 impl Aggregate {
-    fn from_string(seed: &str) -> Self {
+    fn from_string(seed: &str) -> Result<Self> {
         let mut life_times = [0u64; 9];
 
-        seed.split(",")
-            .map(|s| { s.trim().parse::<usize>().unwrap() })
-            .for_each(|n| life_times[n] += 1);
+        for n in parse::separated::<usize>(seed.trim(), ',')? {
+            *life_times.get_mut(n).ok_or_else(|| anyhow!("timer {} is out of range", n))? += 1;
+        }
 
-        Aggregate { life_times }
+        Ok(Aggregate { life_times, day: 1 })
     }
 
+    /// Simulates up to `n_days`, continuing from where the previous call stopped.
     fn simulate(&mut self, n_days: usize) -> u64 {
-        for iter in 1..n_days {
+        for iter in self.day..n_days {
             let day_from = iter % 9;
             let day_to = (iter + 7) % 9;
 
             self.life_times[day_to] += self.life_times[day_from];
         }
+        self.day = self.day.max(n_days);
 
         self.life_times.iter().sum()
     }
 }
 
 impl Solution for TheDay {
-    type Output = u64;
+    type Parsed = Aggregate;
+    type Output1 = u64;
+    type Output2 = u64;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed> {
+        Aggregate::from_string(input)
+    }
 
-    fn part1(&self, input: &str) -> Result<Self::Output> {
-        let mut agg = Aggregate::from_string(input);
-        Ok(agg.simulate(80))
+    fn part1(&self, input: &Self::Parsed) -> Result<Self::Output1> {
+        Ok(input.clone().simulate(80))
     }
 
-    fn part2(&self, input: &str) -> Result<Self::Output> {
-        let mut agg = Aggregate::from_string(input);
-        Ok(agg.simulate(256))
+    fn part2(&self, input: &Self::Parsed) -> Result<Self::Output2> {
+        Ok(input.clone().simulate(256))
+    }
+
+    fn solve_both(&self, input: &Self::Parsed) -> Option<Result<(Self::Output1, Self::Output2)>> {
+        let mut agg = input.clone();
+        let after_80 = agg.simulate(80);
+
+        Some(Ok((after_80, agg.simulate(256))))
     }
 }
@@ -1,5 +1,5 @@
-use anyhow::Result;
-use crate::common::{ Solution, Day };
+use anyhow::{ Result, anyhow };
+use crate::common::{ Solution, Day, parse };
 use super::YEAR;
 
 pub type TheDay = Day<YEAR, 7>;
@@ -15,29 +15,45 @@ fn fuel_func_p2(positions: &[i32], pos: i32) -> i32 {
     }).sum()
 }
 
-fn min_fuel(pos: &[i32], fuel_func: &mut dyn FnMut(&[i32], i32) -> i32) -> i32 {
-    let n_pos = pos.iter().max().unwrap();
+fn min_fuel(pos: &[i32], fuel_func: &mut dyn FnMut(&[i32], i32) -> i32) -> Result<i32> {
+    let (Some(&min_pos), Some(&max_pos)) = (pos.iter().min(), pos.iter().max()) else {
+        return Err(anyhow!("No crab positions"));
+    };
 
-    let (_, min_fuel) = (0..*n_pos)
-            .map(|p| (*fuel_func)(&pos, p))
-            .enumerate()
-            .min_by_key(|(_, fuel)| *fuel).unwrap();
+    Ok((min_pos..=max_pos)
+        .map(|p| (*fuel_func)(pos, p))
+        .fold(i32::MAX, i32::min))
+}
 
-    min_fuel
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_aoc21_7_min_fuel() {
+        assert_eq!(min_fuel(&[0], &mut fuel_func_p1).unwrap(), 0);
+        assert_eq!(min_fuel(&[0, 0], &mut fuel_func_p2).unwrap(), 0);
+        assert_eq!(min_fuel(&[0, 3, 3], &mut fuel_func_p1).unwrap(), 3);
+        assert!(min_fuel(&[], &mut fuel_func_p1).is_err());
+        assert_eq!(min_fuel(&[-2], &mut fuel_func_p1).unwrap(), 0);
+        assert_eq!(min_fuel(&[-5, -1, 2], &mut fuel_func_p1).unwrap(), 7);
+    }
 }
 
 impl Solution for TheDay {
-    type Output = i32;
+    type Parsed = Vec<i32>;
+    type Output1 = i32;
+    type Output2 = i32;
 
-    fn part1(&self, input: &str) -> Result<Self::Output> {
-        let pos: Vec<i32> = input.split(",").map(|s| s.trim().parse::<i32>().unwrap()).collect();
-
-        Ok(min_fuel(&pos, &mut fuel_func_p1))
+    fn parse(&self, input: &str) -> Result<Self::Parsed> {
+        parse::separated(input.trim(), ',')
     }
 
-    fn part2(&self, input: &str) -> Result<Self::Output> {
-        let pos: Vec<i32> = input.split(",").map(|s| s.trim().parse::<i32>().unwrap()).collect();
+    fn part1(&self, input: &Self::Parsed) -> Result<Self::Output1> {
+        min_fuel(input, &mut fuel_func_p1)
+    }
 
-        Ok(min_fuel(&pos, &mut fuel_func_p2))
+    fn part2(&self, input: &Self::Parsed) -> Result<Self::Output2> {
+        min_fuel(input, &mut fuel_func_p2)
     }
 }
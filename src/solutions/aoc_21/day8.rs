@@ -1,24 +1,24 @@
-use anyhow::Result;
-use crate::common::{ Solution, Day };
+use anyhow::{ Result, anyhow };
+use crate::common::{ Solution, Day, parse };
 use super::YEAR;
 
 pub type TheDay = Day<YEAR, 8>;
 
-fn digit_to_byte(digit: &str) -> u8 {
+fn digit_to_byte(digit: &str) -> Result<u8> {
     digit
         .chars()
         .map(|c| 
             match c {
-                'a' => 0b0000001,
-                'b' => 0b0000010,
-                'c' => 0b0000100,
-                'd' => 0b0001000,
-                'e' => 0b0010000,
-                'f' => 0b0100000,
-                'g' => 0b1000000,
-                _ => panic!("Unexpected char: {}", c)
+                'a' => Ok(0b0000001),
+                'b' => Ok(0b0000010),
+                'c' => Ok(0b0000100),
+                'd' => Ok(0b0001000),
+                'e' => Ok(0b0010000),
+                'f' => Ok(0b0100000),
+                'g' => Ok(0b1000000),
+                _ => Err(anyhow!("unexpected segment '{}' in '{}'", c, digit)),
             }
-        ).reduce(|a, b| a | b).unwrap()
+        ).try_fold(0, |a, b| Ok(a | b?))
 }
 
 struct Decoder {
@@ -27,46 +27,58 @@ struct Decoder {
 }
 
 impl Decoder {
-    fn from_string(data: &str) -> Self {
-        let digits: Vec<(usize, u8)> = data.trim()
-            .split(" ")
-            .map(|s| (s.len(), digit_to_byte(s)))
-            .collect();
-
-        let four = digits.iter().find(|(l,_)| *l == 4).map(|(_,v)| *v).unwrap();
-        let seven = digits.iter().find(|(l,_)| *l == 3).map(|(_,v)| *v).unwrap();
-
-        Self { four, seven }
+    fn from_string(data: &str) -> Result<Self> {
+        let digits: Vec<(usize, u8)> = data.split_whitespace()
+            .map(|s| Ok((s.len(), digit_to_byte(s)?)))
+            .collect::<Result<_>>()?;
+        let find = |len: usize| digits.iter()
+            .find(|(l,_)| *l == len)
+            .map(|(_,v)| *v)
+            .ok_or_else(|| anyhow!("no pattern of {} segments in '{}'", len, data.trim()));
+
+        Ok(Self { four: find(4)?, seven: find(3)? })
     }
 
-    fn decode(&self, digit: &str) -> usize {
+    fn decode(&self, digit: &str) -> Result<usize> {
         fn intersect_len(a: u8, b: u8) -> usize {
             (a & b).count_ones() as usize
         }
 
-        match (digit.len(), digit_to_byte(digit)) {
-            (2, _) => 1,
-            (3, _) => 7,
-            (4, _) => 4,
-            (5, d) if intersect_len(d, self.seven) == 3 => 3,
-            (5, d) if intersect_len(d, self.four ) == 3 => 5,
-            (5, d) if intersect_len(d, self.four ) == 2 => 2,
-            (6, d) if intersect_len(d, self.four ) == 4 => 9,
-            (6, d) if intersect_len(d, self.seven) == 3 => 0,
-            (6, d) if intersect_len(d, self.four ) == 3 => 6, 
-            (7, _) => 8,
-            (l, d) => panic!("Unexpected decoding condition: len = {}, digit = {:07b}", l, d), 
+        match (digit.len(), digit_to_byte(digit)?) {
+            (2, _) => Ok(1),
+            (3, _) => Ok(7),
+            (4, _) => Ok(4),
+            (5, d) if intersect_len(d, self.seven) == 3 => Ok(3),
+            (5, d) if intersect_len(d, self.four ) == 3 => Ok(5),
+            (5, d) if intersect_len(d, self.four ) == 2 => Ok(2),
+            (6, d) if intersect_len(d, self.four ) == 4 => Ok(9),
+            (6, d) if intersect_len(d, self.seven) == 3 => Ok(0),
+            (6, d) if intersect_len(d, self.four ) == 3 => Ok(6), 
+            (7, _) => Ok(8),
+            (l, d) => Err(anyhow!("unexpected decoding condition: len = {}, digit = {:07b}", l, d)), 
         }
     }
 
-    fn decode_number(&self, number: &str) -> usize {
-        let digits: Vec<&str> = number.trim().split(" ").collect();
+    fn decode_number(&self, number: &str) -> Result<usize> {
+        number.split_whitespace()
+            .try_fold(0, |n, s| Ok(n * 10 + self.decode(s)?))
+    }
+}
 
-        digits.iter()
-            .map(|s| self.decode(s))
-            .enumerate()
-            .map(|(i, v)| v * 10_usize.pow((digits.len() - i - 1).try_into().unwrap()))
-            .sum()
+/// One line of notes: the decoder learnt from the ten patterns and the four output digits.
+pub struct Note {
+    decoder: Decoder,
+    output: String,
+}
+
+impl Note {
+    fn from_string(s: &str) -> Result<Self> {
+        let (digits, query) = s.split_once('|').ok_or_else(|| anyhow!("missing '|' in '{}'", s))?;
+        let output = query.trim().to_string();
+
+        output.split_whitespace().try_for_each(|d| digit_to_byte(d).map(|_| ()))?;
+
+        Ok(Note { decoder: Decoder::from_string(digits)?, output })
     }
 }
 
@@ -76,32 +88,37 @@ mod tests {
 
     #[test]
     pub fn test_aoc21_8_decoder() {
-        let decoder = Decoder::from_string("acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab");
-
-        assert_eq!(decoder.decode("ba"), 1);
-        assert_eq!(decoder.decode("acedbfg"), 8);
-        assert_eq!(decoder.decode("fabe"), 4);
-        assert_eq!(decoder.decode("dab"), 7);
-        assert_eq!(decoder.decode("gcdfa"), 2);
-        assert_eq!(decoder.decode("fbcad"), 3);
-        assert_eq!(decoder.decode("cdfbe"), 5);
-        assert_eq!(decoder.decode("cdfgeb"), 6);
-        assert_eq!(decoder.decode("cefabd"), 9);
-        assert_eq!(decoder.decode("cagedb"), 0);
-
-        assert_eq!(decoder.decode_number("cdfeb fcadb cdfeb cdbaf"), 5353);
+        let decoder = Decoder::from_string("acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab").unwrap();
+
+        assert_eq!(decoder.decode("ba").unwrap(), 1);
+        assert_eq!(decoder.decode("acedbfg").unwrap(), 8);
+        assert_eq!(decoder.decode("fabe").unwrap(), 4);
+        assert_eq!(decoder.decode("dab").unwrap(), 7);
+        assert_eq!(decoder.decode("gcdfa").unwrap(), 2);
+        assert_eq!(decoder.decode("fbcad").unwrap(), 3);
+        assert_eq!(decoder.decode("cdfbe").unwrap(), 5);
+        assert_eq!(decoder.decode("cdfgeb").unwrap(), 6);
+        assert_eq!(decoder.decode("cefabd").unwrap(), 9);
+        assert_eq!(decoder.decode("cagedb").unwrap(), 0);
+
+        assert_eq!(decoder.decode_number("cdfeb fcadb cdfeb cdbaf").unwrap(), 5353);
+        assert!(Note::from_string("acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb xcadb").is_err());
     }
 }
 
 impl Solution for TheDay {
-    type Output = usize;
+    type Parsed = Vec<Note>;
+    type Output1 = usize;
+    type Output2 = usize;
 
-    fn part1(&self, input: &str) -> Result<Self::Output> {
+    fn parse(&self, input: &str) -> Result<Self::Parsed> {
+        parse::lines(input, Note::from_string)
+    }
+
+    fn part1(&self, input: &Self::Parsed) -> Result<Self::Output1> {
         Ok( 
-            input.lines()
-                .map(|s| s.split(" ")
-                          .skip_while(|s| *s != "|")
-                          .skip(1)
+            input.iter()
+                .map(|n| n.output.split(" ")
                           .map(|s| s.len())
                           .filter(|l| *l == 2 || *l == 3 || *l == 4 || *l == 7)
                           .count())
@@ -109,14 +126,9 @@ impl Solution for TheDay {
         )
     }
 
-    fn part2(&self, input: &str) -> Result<Self::Output> {
-        Ok(
-            input.lines()
-            .map(|s| {
-                let (digits, query) = s.split_at(s.find("|").unwrap());
-                Decoder::from_string(digits).decode_number(query.replace("|", "").trim())
-            })
+    fn part2(&self, input: &Self::Parsed) -> Result<Self::Output2> {
+        input.iter()
+            .map(|n| n.decoder.decode_number(&n.output))
             .sum()
-        )
     }
 }
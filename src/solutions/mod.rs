@@ -1,22 +1,147 @@
-use crate::common::{ Solvable, AocDate };
+use crate::common::{ Solvable, AocDate, Part };
 use lazy_static::lazy_static;
-use std::collections::HashMap;
+use std::fmt;
 
-mod aoc_20;
-mod aoc_21;
+pub type ThreadSafeSolvable = dyn Solvable + Send + Sync;
 
-type ThreadSafeSolvable = dyn Solvable + Send + Sync;
+/// True if some key occurs more than once; used by the registration macros
+/// to reject duplicate days and years at compile time.
+pub const fn has_duplicates(keys: &[u32]) -> bool {
+    let mut i = 0;
+    while i < keys.len() {
+        let mut j = i + 1;
+        while j < keys.len() {
+            if keys[i] == keys[j] {
+                return true;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+
+    false
+}
+
+/// Declares the day modules of a year and registers the `TheDay` solution of each:
+///
+/// ```ignore
+/// solutions!(2021 => day1, day2, day3);
+/// ```
+///
+/// Each module must define `TheDay` as `Day<YEAR, N>`. Two modules solving the
+/// same day fail to compile.
+macro_rules! solutions {
+    ($year:literal => $($day:ident),+ $(,)?) => {
+        $(mod $day;)+
+
+        pub const YEAR: u32 = $year;
+
+        const _: () = assert!(
+            !$crate::solutions::has_duplicates(&[$($day::TheDay::DATE.day),+]),
+            "Several modules solve the same day"
+        );
+
+        pub fn solutions() -> Vec<Box<$crate::solutions::ThreadSafeSolvable>> {
+            vec![$(Box::new(<$day::TheDay as Default>::default())),+]
+        }
+    };
+}
+
+/// Declares the year modules, keyed by year, and collects their solutions into the registry:
+///
+/// ```ignore
+/// years!(2020 => aoc_20, 2021 => aoc_21);
+/// ```
+///
+/// A module whose `solutions!` call names another year than its key, or a year
+/// registered twice, fails to compile.
+macro_rules! years {
+    ($($year:literal => $module:ident),+ $(,)?) => {
+        $(mod $module;)+
+
+        const _: () = {
+            $(assert!($module::YEAR == $year, concat!(stringify!($module), " doesn't hold the solutions of ", stringify!($year)));)+
+            assert!(!has_duplicates(&[$($year),+]), "Several modules register the same year");
+        };
+
+        fn registered() -> Vec<Box<ThreadSafeSolvable>> {
+            let mut all = Vec::new();
+            $(all.extend($module::solutions());)+
+            all
+        }
+    };
+}
+
+years!(2020 => aoc_20, 2021 => aoc_21);
 
 lazy_static! {
-    static ref ALL_SOLUTIONS: HashMap<u32, &'static Vec<Box<ThreadSafeSolvable>>> = {
-        let mut m = HashMap::new();
-        m.insert(aoc_20::YEAR, aoc_20::SOLUTIONS.as_ref());
-        m.insert(aoc_21::YEAR, aoc_21::SOLUTIONS.as_ref());
-        m
+    static ref ALL_SOLUTIONS: Vec<Box<ThreadSafeSolvable>> = {
+        let mut all = registered();
+        all.sort_by_key(|s| (s.get_date().year, s.get_date().day));
+        all
     };
 }
 
-pub fn find(date: &AocDate) -> Option<&Box<ThreadSafeSolvable>> {
-    ALL_SOLUTIONS.get(&date.year).map(|&v| v.iter().find(|&e| e.get_date() == *date)).unwrap()
+/// Why no solution could be found for a date.
+#[derive(Debug, PartialEq, Eq)]
+pub enum FindError {
+    /// No solution at all is registered for the year
+    UnknownYear(u32),
+    /// The year has solutions, but not for this day
+    UnimplementedDay(AocDate),
 }
 
+impl fmt::Display for FindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FindError::UnknownYear(year) => write!(f, "No solutions registered for year {}", year),
+            FindError::UnimplementedDay(date) => write!(f, "No solution registered for {}", date),
+        }
+    }
+}
+
+impl std::error::Error for FindError {}
+
+pub fn find(date: &AocDate) -> Result<&'static ThreadSafeSolvable, FindError> {
+    if let Some(solution) = ALL_SOLUTIONS.iter().find(|e| e.get_date() == *date) {
+        Ok(solution.as_ref())
+    } else if ALL_SOLUTIONS.iter().any(|e| e.get_date().year == date.year) {
+        Err(FindError::UnimplementedDay(*date))
+    } else {
+        Err(FindError::UnknownYear(date.year))
+    }
+}
+
+/// Every registered solution, ordered by date.
+pub fn all() -> Vec<&'static ThreadSafeSolvable> {
+    ALL_SOLUTIONS.iter().map(|e| e.as_ref()).collect()
+}
+
+/// Registered dates, ordered, with whether part 1 and part 2 are implemented.
+pub fn list() -> Vec<(AocDate, bool, bool)> {
+    ALL_SOLUTIONS.iter()
+        .map(|s| (s.get_date(), s.implemented(Part::One), s.implemented(Part::Two)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_registry() {
+        assert!(!has_duplicates(&[1, 2, 3]));
+        assert!(has_duplicates(&[1, 2, 1]));
+
+        let dates: Vec<AocDate> = all().iter().map(|s| s.get_date()).collect();
+        assert!(dates.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(find(&AocDate { year: 2021, day: 11 }).map(|s| s.get_date()), Ok(AocDate { year: 2021, day: 11 }));
+        assert_eq!(find(&AocDate { year: 2019, day: 1 }).err(), Some(FindError::UnknownYear(2019)));
+        assert_eq!(find(&AocDate { year: 2021, day: 24 }).err(), Some(FindError::UnimplementedDay(AocDate { year: 2021, day: 24 })));
+
+        let listed = list();
+        assert_eq!(listed.len(), dates.len());
+        assert!(listed.contains(&(AocDate { year: 2020, day: 1 }, false, false)));
+        assert!(listed.contains(&(AocDate { year: 2021, day: 1 }, true, true)));
+    }
+}
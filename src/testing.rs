@@ -0,0 +1,71 @@
+use std::io::{ BufRead, BufReader, Read, Write };
+use std::net::TcpListener;
+use std::sync::{ Arc, Mutex };
+use std::thread;
+
+/// Minimal HTTP server standing in for adventofcode.com in tests.
+/// Answers each incoming connection with the next canned `(status, body)` response
+/// and records the raw requests it received.
+pub struct MockServer {
+    port: u16,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockServer {
+    pub fn start(responses: Vec<(u16, String)>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (stream, _) = match listener.accept() {
+                    Ok(conn) => conn,
+                    Err(_) => return,
+                };
+                let mut reader = BufReader::new(stream);
+                let mut request = String::new();
+                let mut content_length = 0;
+
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                        break;
+                    }
+
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap_or(0);
+                        }
+                    }
+
+                    request.push_str(&line);
+                }
+
+                let mut payload = vec![0u8; content_length];
+                let _ = reader.read_exact(&mut payload);
+                request.push_str("\r\n");
+                request.push_str(&String::from_utf8_lossy(&payload));
+
+                recorded.lock().unwrap().push(request);
+
+                let response = format!(
+                    "HTTP/1.1 {} Mock\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status, body.len(), body
+                );
+                let _ = reader.get_mut().write_all(response.as_bytes());
+            }
+        });
+
+        MockServer { port, requests }
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}